@@ -1,6 +1,5 @@
-use regex::Regex;
-use std::io::{self, BufReader, BufRead};
-use battlelayer::conn::ConnectionBuilder;
+use battlelayer::conn::{Body, ConnectionBuilder};
+use std::io::{self, BufRead, BufReader};
 
 #[tokio::main]
 async fn main() {
@@ -8,14 +7,22 @@ async fn main() {
         .connect("109.200.214.230:25515")
         .await
         .unwrap();
-    
+
     let input = BufReader::new(io::stdin());
-    let words_regex = Regex::new(r#"(?P<word>(\\,|[^,])+)(?:,?)"#).unwrap();
 
     for line_res in input.lines() {
         let line = line_res.unwrap();
-        let words: Vec<_> = words_regex.captures_iter(line.as_str()).map(|c| c["word"].to_string()).collect();
-        let response = conn.send(words).await.unwrap();
+        let body = match Body::parse_command_line(line.as_str()) {
+            Ok(body) => body,
+            Err(err) => {
+                eprintln!("invalid command: {}", err);
+                continue;
+            }
+        };
+        if body.is_empty() {
+            continue;
+        }
+        let response = conn.send_body(body).await.unwrap();
         println!("{}", response);
     }
 }
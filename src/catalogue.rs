@@ -0,0 +1,529 @@
+//! Display names of the BF4 codes found in responses and events.
+//!
+//! Lookups compare codes case-insensitively. Name lookups fall back to
+//! the code itself, so unknown codes from newer patches still display.
+
+use std::fmt;
+
+/// The release a map, game mode or weapon was introduced with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Expansion {
+    Base,
+    ChinaRising,
+    SecondAssault,
+    NavalStrike,
+    DragonsTeeth,
+    FinalStand,
+    /// Free maps released by the community test environment
+    Community,
+}
+
+impl Expansion {
+    pub fn name(self) -> &'static str {
+        match self {
+            Expansion::Base => "Battlefield 4",
+            Expansion::ChinaRising => "China Rising",
+            Expansion::SecondAssault => "Second Assault",
+            Expansion::NavalStrike => "Naval Strike",
+            Expansion::DragonsTeeth => "Dragon's Teeth",
+            Expansion::FinalStand => "Final Stand",
+            Expansion::Community => "Community",
+        }
+    }
+}
+
+impl fmt::Display for Expansion {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.name())
+    }
+}
+
+/// The kind of a weapon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeaponCategory {
+    AssaultRifle,
+    Carbine,
+    Pdw,
+    Lmg,
+    Dmr,
+    SniperRifle,
+    Shotgun,
+    Handgun,
+    Melee,
+    Explosive,
+    Gadget,
+    Vehicle,
+    /// Deaths not caused by a weapon, such as falling
+    Environment,
+    /// Weapons not in the catalogue
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapInfo {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub expansion: Expansion,
+    /// Codes of the game modes the map can be played in
+    pub game_modes: &'static [&'static str],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameModeInfo {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub expansion: Expansion,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeaponInfo {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub category: WeaponCategory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FactionInfo {
+    /// Id used by `vars.teamFactionOverride`
+    pub id: u32,
+    pub code: &'static str,
+    pub name: &'static str,
+}
+
+/// Represents a map and game mode combination not allowed in the map
+/// list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogueError {
+    UnknownMap(String),
+    UnknownGameMode(String),
+    /// The map cannot be played in the game mode.
+    UnsupportedGameMode {
+        map: String,
+        game_mode: String,
+    },
+}
+
+impl fmt::Display for CatalogueError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CatalogueError::UnknownMap(map) => write!(fmt, "unknown map {:?}", map),
+            CatalogueError::UnknownGameMode(mode) => write!(fmt, "unknown game mode {:?}", mode),
+            CatalogueError::UnsupportedGameMode { map, game_mode } => {
+                write!(fmt, "map {:?} cannot be played in {:?}", map, game_mode)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogueError {}
+
+///////////////////////////////////////////////////////////////////////////////
+
+macro_rules! maps {
+    ($($code:expr => $name:expr, $expansion:ident, [$($mode:expr),* $(,)?];)*) => {
+        &[$(MapInfo {
+            code: $code,
+            name: $name,
+            expansion: Expansion::$expansion,
+            game_modes: &[$($mode),*],
+        }),*]
+    };
+}
+
+pub const MAPS: &[MapInfo] = maps! {
+    "MP_Abandoned" => "Zavod 311", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "SquadObliteration0", "GunMaster0",
+    ];
+    "MP_Damage" => "Lancang Dam", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0",
+    ];
+    "MP_Flooded" => "Flood Zone", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0",
+    ];
+    "MP_Journey" => "Golmud Railway", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "SquadObliteration0",
+    ];
+    "MP_Naval" => "Paracel Storm", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0",
+    ];
+    "MP_Prison" => "Operation Locker", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "SquadObliteration0", "GunMaster0",
+    ];
+    "MP_Resort" => "Hainan Resort", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "SquadObliteration0", "GunMaster0",
+    ];
+    "MP_Siege" => "Siege of Shanghai", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "GunMaster0",
+    ];
+    "MP_TheDish" => "Rogue Transmission", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "SquadObliteration0",
+    ];
+    "MP_Tremors" => "Dawnbreaker", Base, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "GunMaster0",
+    ];
+    "XP1_001" => "Silk Road", ChinaRising, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "AirSuperiority0",
+    ];
+    "XP1_002" => "Altai Range", ChinaRising, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "AirSuperiority0",
+    ];
+    "XP1_003" => "Guilin Peaks", ChinaRising, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "AirSuperiority0",
+    ];
+    "XP1_004" => "Dragon Pass", ChinaRising, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "AirSuperiority0",
+    ];
+    "XP0_Caspian" => "Caspian Border 2014", SecondAssault, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CaptureTheFlag0",
+    ];
+    "XP0_Firestorm" => "Operation Firestorm 2014", SecondAssault, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CaptureTheFlag0",
+    ];
+    "XP0_Metro" => "Operation Metro 2014", SecondAssault, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CaptureTheFlag0", "GunMaster0",
+    ];
+    "XP0_Oman" => "Gulf of Oman 2014", SecondAssault, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CaptureTheFlag0",
+    ];
+    "XP2_001" => "Lost Islands", NavalStrike, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CarrierAssaultLarge0",
+        "CarrierAssaultSmall0",
+    ];
+    "XP2_002" => "Nansha Strike", NavalStrike, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CarrierAssaultLarge0",
+        "CarrierAssaultSmall0",
+    ];
+    "XP2_003" => "Wave Breaker", NavalStrike, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CarrierAssaultLarge0",
+        "CarrierAssaultSmall0",
+    ];
+    "XP2_004" => "Operation Mortar", NavalStrike, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CarrierAssaultLarge0",
+        "CarrierAssaultSmall0",
+    ];
+    "XP3_MarketPl" => "Pearl Market", DragonsTeeth, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "Chainlink0", "CaptureTheFlag0",
+        "GunMaster0",
+    ];
+    "XP3_Prpganda" => "Propaganda", DragonsTeeth, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "Chainlink0", "CaptureTheFlag0",
+        "GunMaster0",
+    ];
+    "XP3_UrbanGdn" => "Lumphini Garden", DragonsTeeth, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "Chainlink0", "CaptureTheFlag0",
+        "GunMaster0",
+    ];
+    "XP3_WtrFront" => "Sunken Dragon", DragonsTeeth, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "Chainlink0", "CaptureTheFlag0",
+        "GunMaster0",
+    ];
+    "XP4_Arctic" => "Operation Whiteout", FinalStand, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CaptureTheFlag0",
+    ];
+    "XP4_SubBase" => "Hammerhead", FinalStand, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CaptureTheFlag0",
+    ];
+    "XP4_Titan" => "Hangar 21", FinalStand, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CaptureTheFlag0",
+    ];
+    "XP4_WlkrFtry" => "Giants of Karelia", FinalStand, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Elimination0", "Obliteration",
+        "RushLarge0", "SquadDeathMatch0", "TeamDeathMatch0", "CaptureTheFlag0",
+    ];
+    "XP5_Night_01" => "Zavod: Graveyard Shift", Community, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Obliteration", "RushLarge0",
+        "SquadDeathMatch0", "TeamDeathMatch0",
+    ];
+    "XP6_CMP" => "Operation Outbreak", Community, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Obliteration", "RushLarge0",
+        "SquadDeathMatch0", "TeamDeathMatch0",
+    ];
+    "XP7_Valley" => "Dragon Valley 2015", Community, [
+        "ConquestLarge0", "ConquestSmall0", "Domination0", "Obliteration", "RushLarge0",
+        "SquadDeathMatch0", "TeamDeathMatch0",
+    ];
+};
+
+macro_rules! game_modes {
+    ($($code:expr => $name:expr, $expansion:ident;)*) => {
+        &[$(GameModeInfo {
+            code: $code,
+            name: $name,
+            expansion: Expansion::$expansion,
+        }),*]
+    };
+}
+
+pub const GAME_MODES: &[GameModeInfo] = game_modes! {
+    "ConquestLarge0" => "Conquest Large", Base;
+    "ConquestSmall0" => "Conquest", Base;
+    "Domination0" => "Domination", Base;
+    "Elimination0" => "Defuse", Base;
+    "Obliteration" => "Obliteration", Base;
+    "RushLarge0" => "Rush", Base;
+    "SquadDeathMatch0" => "Squad Deathmatch", Base;
+    "TeamDeathMatch0" => "Team Deathmatch", Base;
+    "SquadObliteration0" => "Squad Obliteration", Base;
+    "GunMaster0" => "Gun Master", Base;
+    "AirSuperiority0" => "Air Superiority", ChinaRising;
+    "CaptureTheFlag0" => "Capture the Flag", SecondAssault;
+    "CarrierAssaultLarge0" => "Carrier Assault Large", NavalStrike;
+    "CarrierAssaultSmall0" => "Carrier Assault", NavalStrike;
+    "Chainlink0" => "Chain Link", DragonsTeeth;
+};
+
+macro_rules! weapons {
+    ($($code:expr => $name:expr, $category:ident;)*) => {
+        &[$(WeaponInfo {
+            code: $code,
+            name: $name,
+            category: WeaponCategory::$category,
+        }),*]
+    };
+}
+
+pub const WEAPONS: &[WeaponInfo] = weapons! {
+    "U_AEK971" => "AEK-971", AssaultRifle;
+    "U_AK12" => "AK-12", AssaultRifle;
+    "U_M416" => "M416", AssaultRifle;
+    "U_SCAR-H" => "SCAR-H", AssaultRifle;
+    "U_M16A4" => "M16A4", AssaultRifle;
+    "U_F2000" => "F2000", AssaultRifle;
+    "U_AUG" => "AUG A3", AssaultRifle;
+    "U_FAMAS" => "FAMAS", AssaultRifle;
+    "U_L85A2" => "L85A2", AssaultRifle;
+    "U_QBZ951" => "QBZ-95-1", AssaultRifle;
+    "U_A91" => "A-91", Carbine;
+    "U_ACR" => "ACW-R", Carbine;
+    "U_AK5C" => "AK 5C", Carbine;
+    "U_AKU12" => "AKU-12", Carbine;
+    "U_G36C" => "G36C", Carbine;
+    "U_M4A1" => "M4", Carbine;
+    "U_MTAR21" => "MTAR-21", Carbine;
+    "U_MP7" => "MP7", Pdw;
+    "U_P90" => "P90", Pdw;
+    "U_UMP45" => "UMP-45", Pdw;
+    "U_CBJ-MS" => "CBJ-MS", Pdw;
+    "U_PDR" => "PDW-R", Pdw;
+    "U_M249" => "M249", Lmg;
+    "U_RPK12" => "RPK-12", Lmg;
+    "U_Pecheneg" => "PKP Pecheneg", Lmg;
+    "U_LSAT" => "LSAT", Lmg;
+    "U_SKS" => "SKS", Dmr;
+    "U_M39EBR" => "M39 EMR", Dmr;
+    "U_SVD12" => "SVD-12", Dmr;
+    "U_MK11" => "MK11 MOD 0", Dmr;
+    "U_M40A5" => "M40A5", SniperRifle;
+    "U_M98B" => "M98B", SniperRifle;
+    "U_SRR61" => "SRR-61", SniperRifle;
+    "U_CS-LR4" => "CS-LR4", SniperRifle;
+    "U_Scout" => "Scout Elite", SniperRifle;
+    "U_L96A1" => "338-Recon", SniperRifle;
+    "U_870" => "870 MCS", Shotgun;
+    "U_M1014" => "M1014", Shotgun;
+    "U_SPAS12" => "SPAS-12", Shotgun;
+    "U_SAIGA_20K" => "Saiga 12K", Shotgun;
+    "U_UTAS" => "UTS 15", Shotgun;
+    "U_M9" => "M9", Handgun;
+    "U_P226" => "P226", Handgun;
+    "U_Glock18" => "G18", Handgun;
+    "U_Taurus44" => ".44 Magnum", Handgun;
+    "U_M1911" => "M1911", Handgun;
+    "U_MP443" => "MP-443", Handgun;
+    "Melee" => "Knife", Melee;
+    "U_Defib" => "Defibrillator", Gadget;
+    "U_Repairtool" => "Repair Tool", Gadget;
+    "U_C4" => "C4", Explosive;
+    "U_C4_Support" => "C4", Explosive;
+    "U_Claymore" => "Claymore", Explosive;
+    "U_M15" => "M15 AT Mine", Explosive;
+    "U_M67" => "M67 Grenade", Explosive;
+    "U_V40" => "V40 Mini", Explosive;
+    "U_SMAW" => "SMAW", Explosive;
+    "U_RPG7" => "RPG-7V2", Explosive;
+    "U_FGM148" => "FGM-148 Javelin", Explosive;
+    "U_SRAW" => "MBT LAW", Explosive;
+    "U_Sa18IGLA" => "SA-18 IGLA", Explosive;
+    "U_FIM92" => "FIM-92 Stinger", Explosive;
+    "U_M320_HE" => "M320 HE", Explosive;
+    "U_XM25" => "XM25 Airburst", Explosive;
+    "RoadKill" => "Roadkill", Vehicle;
+    "SoldierCollision" => "Collision", Environment;
+    "DamageArea" => "Out of Bounds", Environment;
+    "Death" => "Death", Environment;
+    "Suicide" => "Suicide", Environment;
+};
+
+pub const FACTIONS: &[FactionInfo] = &[
+    FactionInfo {
+        id: 0,
+        code: "US",
+        name: "United States",
+    },
+    FactionInfo {
+        id: 1,
+        code: "RU",
+        name: "Russia",
+    },
+    FactionInfo {
+        id: 2,
+        code: "CN",
+        name: "China",
+    },
+];
+
+///////////////////////////////////////////////////////////////////////////////
+
+pub fn map(code: &str) -> Option<&'static MapInfo> {
+    MAPS.iter().find(|m| m.code.eq_ignore_ascii_case(code))
+}
+
+pub fn game_mode(code: &str) -> Option<&'static GameModeInfo> {
+    GAME_MODES
+        .iter()
+        .find(|m| m.code.eq_ignore_ascii_case(code))
+}
+
+pub fn weapon(code: &str) -> Option<&'static WeaponInfo> {
+    WEAPONS.iter().find(|w| w.code.eq_ignore_ascii_case(code))
+}
+
+pub fn faction(id: u32) -> Option<&'static FactionInfo> {
+    FACTIONS.iter().find(|f| f.id == id)
+}
+
+/// Returns the display name of a map, or the code if unknown.
+pub fn map_name(code: &str) -> &str {
+    map(code).map_or(code, |m| m.name)
+}
+
+/// Returns the display name of a game mode, or the code if unknown.
+pub fn game_mode_name(code: &str) -> &str {
+    game_mode(code).map_or(code, |m| m.name)
+}
+
+/// Returns the display name of a weapon, or the code if unknown.
+pub fn weapon_name(code: &str) -> &str {
+    weapon(code).map_or(code, |w| w.name)
+}
+
+/// Returns the category of a weapon.
+///
+/// Vehicle weapons are reported by their asset path, such as
+/// `Gameplay/Vehicles/...`, and are recognised as such.
+pub fn weapon_category(code: &str) -> WeaponCategory {
+    match weapon(code) {
+        Some(weapon) => weapon.category,
+        None if code.contains("Vehicles/") => WeaponCategory::Vehicle,
+        None => WeaponCategory::Unknown,
+    }
+}
+
+/// Checks a map can be played in a game mode, as added with
+/// `mapList.add`.
+///
+/// The check is advisory: the server has the final say, and the client
+/// does not make it before changing the map list, as it also speaks to
+/// games other than BF4.
+pub fn validate_map_mode(map_code: &str, game_mode_code: &str) -> Result<(), CatalogueError> {
+    let map = map(map_code).ok_or_else(|| CatalogueError::UnknownMap(map_code.to_string()))?;
+    let game_mode = game_mode(game_mode_code)
+        .ok_or_else(|| CatalogueError::UnknownGameMode(game_mode_code.to_string()))?;
+    if map.game_modes.contains(&game_mode.code) {
+        Ok(())
+    } else {
+        Err(CatalogueError::UnsupportedGameMode {
+            map: map.code.to_string(),
+            game_mode: game_mode.code.to_string(),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_test() {
+        assert_eq!(map_name("MP_Prison"), "Operation Locker");
+        assert_eq!(map_name("mp_prison"), "Operation Locker");
+        assert_eq!(map("XP2_001").unwrap().expansion, Expansion::NavalStrike);
+        assert_eq!(map_name("MP_Unreleased"), "MP_Unreleased");
+        assert_eq!(game_mode_name("ConquestLarge0"), "Conquest Large");
+        assert_eq!(weapon_name("U_AEK971"), "AEK-971");
+        assert_eq!(weapon_category("U_AEK971"), WeaponCategory::AssaultRifle);
+        assert_eq!(
+            weapon_category("Gameplay/Vehicles/AH6/AH6_Littlebird"),
+            WeaponCategory::Vehicle
+        );
+        assert_eq!(weapon_category("U_Unknown"), WeaponCategory::Unknown);
+        assert_eq!(faction(1).unwrap().code, "RU");
+        assert!(faction(9).is_none());
+    }
+
+    #[test]
+    fn catalogue_consistency_test() {
+        for map in MAPS {
+            for mode in map.game_modes {
+                assert!(game_mode(mode).is_some(), "{} of {}", mode, map.code);
+            }
+        }
+    }
+
+    #[test]
+    fn validate_map_mode_test() {
+        assert_eq!(validate_map_mode("MP_Prison", "ConquestLarge0"), Ok(()));
+        assert_eq!(validate_map_mode("XP2_003", "carrierassaultlarge0"), Ok(()));
+        assert_eq!(
+            validate_map_mode("MP_Prison", "CarrierAssaultLarge0"),
+            Err(CatalogueError::UnsupportedGameMode {
+                map: "MP_Prison".to_string(),
+                game_mode: "CarrierAssaultLarge0".to_string(),
+            })
+        );
+        // Game modes are listed per map, not per expansion.
+        assert_eq!(validate_map_mode("MP_Prison", "SquadObliteration0"), Ok(()));
+        assert!(validate_map_mode("MP_Siege", "SquadObliteration0").is_err());
+        assert_eq!(validate_map_mode("MP_Siege", "GunMaster0"), Ok(()));
+        assert!(validate_map_mode("MP_Naval", "GunMaster0").is_err());
+        assert_eq!(
+            validate_map_mode("MP_Nowhere", "ConquestLarge0"),
+            Err(CatalogueError::UnknownMap("MP_Nowhere".to_string()))
+        );
+        assert_eq!(
+            validate_map_mode("MP_Prison", "Arcade0"),
+            Err(CatalogueError::UnknownGameMode("Arcade0".to_string()))
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+
+use futures_core::Stream;
+use futures_util::stream;
+
+use super::{request, Client, CommandError};
+use crate::conn::Word;
+use crate::types::{BanEntry, BanList, PlayerId, Timeout};
+
+/// The most entries `banList.list` returns per call.
+pub const BAN_LIST_PAGE_SIZE: usize = 100;
+
+impl Client {
+    /// Bans a player, with an optional reason shown to them.
+    pub async fn ban_add(
+        &mut self,
+        id: &PlayerId,
+        timeout: Timeout,
+        reason: Option<&str>,
+    ) -> Result<(), CommandError> {
+        let mut body = request("banList.add");
+        body.push_words(id);
+        body.push_words(&timeout);
+        if let Some(reason) = reason {
+            body.push(&Word::new(reason)?);
+        }
+        self.command(body).await.map(drop)
+    }
+
+    /// Lifts the ban of a player.
+    pub async fn ban_remove(&mut self, id: &PlayerId) -> Result<(), CommandError> {
+        let mut body = request("banList.remove");
+        body.push_words(id);
+        self.command(body).await.map(drop)
+    }
+
+    /// Lists up to [`BAN_LIST_PAGE_SIZE`] entries, starting at the offset.
+    pub async fn ban_list(&mut self, offset: usize) -> Result<Vec<BanEntry>, CommandError> {
+        let mut body = request("banList.list");
+        if offset > 0 {
+            body.push(&offset);
+        }
+        self.query::<BanList>(body).await.map(|list| list.entries)
+    }
+
+    /// Returns a stream of all entries, requesting a page at a time.
+    ///
+    /// The stream ends after the first error.
+    pub fn ban_list_all(&mut self) -> impl Stream<Item = Result<BanEntry, CommandError>> + '_ {
+        let pages = BanPages {
+            client: self,
+            offset: 0,
+            entries: VecDeque::new(),
+            done: false,
+        };
+        stream::unfold(pages, |mut pages| async move {
+            if pages.entries.is_empty() && !pages.done {
+                match pages.client.ban_list(pages.offset).await {
+                    Ok(entries) => {
+                        pages.done = entries.len() < BAN_LIST_PAGE_SIZE;
+                        pages.offset += entries.len();
+                        pages.entries.extend(entries);
+                    }
+                    Err(err) => {
+                        pages.done = true;
+                        return Some((Err(err), pages));
+                    }
+                }
+            }
+            let entry = pages.entries.pop_front()?;
+            Some((Ok(entry), pages))
+        })
+    }
+
+    /// Removes all entries from the ban list.
+    pub async fn ban_clear(&mut self) -> Result<(), CommandError> {
+        self.command(request("banList.clear")).await.map(drop)
+    }
+
+    /// Saves the ban list to disk.
+    pub async fn ban_save(&mut self) -> Result<(), CommandError> {
+        self.command(request("banList.save")).await.map(drop)
+    }
+
+    /// Loads the ban list from disk, replacing the current list.
+    pub async fn ban_load(&mut self) -> Result<(), CommandError> {
+        self.command(request("banList.load")).await.map(drop)
+    }
+}
+
+struct BanPages<'a> {
+    client: &'a mut Client,
+    offset: usize,
+    entries: VecDeque<BanEntry>,
+    done: bool,
+}
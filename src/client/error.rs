@@ -0,0 +1,70 @@
+use std::fmt;
+
+use crate::conn::{BodyError, Error};
+use crate::profile::GameProfile;
+use crate::types::vars::VarError;
+
+/// Represents a failed command.
+#[derive(Debug)]
+pub enum CommandError {
+    /// The request could not be sent or its response not received.
+    Connection(Error),
+    /// The server responded with a status other than `OK`.
+    Response { status: String },
+    /// An argument could not be encoded, or the response decoded.
+    Body(BodyError),
+    /// A server variable or its value was rejected before sending.
+    Var(VarError),
+    /// The game of the client's profile does not know the command.
+    Unsupported {
+        command: String,
+        profile: GameProfile,
+    },
+    /// The server reported a game without a profile.
+    UnknownGame(String),
+}
+
+impl CommandError {
+    /// Returns the status of an error response, if any.
+    pub fn status(&self) -> Option<&str> {
+        match self {
+            CommandError::Response { status } => Some(status),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::Connection(err) => write!(fmt, "connection error: {:?}", err),
+            CommandError::Response { status } => write!(fmt, "server responded {:?}", status),
+            CommandError::Body(err) => err.fmt(fmt),
+            CommandError::Var(err) => err.fmt(fmt),
+            CommandError::Unsupported { command, profile } => {
+                write!(fmt, "{} does not support {:?}", profile, command)
+            }
+            CommandError::UnknownGame(game) => write!(fmt, "unknown game {:?}", game),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<Error> for CommandError {
+    fn from(err: Error) -> Self {
+        CommandError::Connection(err)
+    }
+}
+
+impl From<BodyError> for CommandError {
+    fn from(err: BodyError) -> Self {
+        CommandError::Body(err)
+    }
+}
+
+impl From<VarError> for CommandError {
+    fn from(err: VarError) -> Self {
+        CommandError::Var(err)
+    }
+}
@@ -0,0 +1,173 @@
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use futures_channel::mpsc;
+use futures_core::stream::Stream;
+use futures_util::future;
+use futures_util::stream::StreamExt;
+use tower_service::Service;
+
+use crate::conn::{Error, Request, Response};
+use crate::event::ServerEvent;
+use crate::profile::GameProfile;
+
+/// A connection handler parsing the requests of the server into events,
+/// and sending every event to each subscriber.
+///
+/// Requests are answered `OK`. Those that fail to parse in the dialect
+/// of the profile are sent as [`ServerEvent::Other`].
+pub struct EventHandler {
+    shared: Arc<Mutex<Shared>>,
+}
+
+/// Subscribes to the events of an [`EventHandler`].
+#[derive(Clone)]
+pub struct Events {
+    shared: Arc<Mutex<Shared>>,
+}
+
+struct Shared {
+    profile: GameProfile,
+    subscribers: Subscribers<ServerEvent>,
+}
+
+/// The senders of the subscriptions to a stream of values.
+pub(crate) struct Subscribers<T> {
+    senders: Vec<mpsc::UnboundedSender<T>>,
+}
+
+impl<T: Clone> Subscribers<T> {
+    pub fn new() -> Self {
+        Self {
+            senders: Vec::new(),
+        }
+    }
+
+    /// Returns a stream of the values sent from now on.
+    pub fn subscribe(&mut self) -> mpsc::UnboundedReceiver<T> {
+        let (tx, rx) = mpsc::unbounded();
+        self.senders.push(tx);
+        rx
+    }
+
+    /// Sends a value to each subscriber, forgetting those that dropped
+    /// their stream.
+    pub fn send(&mut self, value: &T) {
+        self.senders
+            .retain(|tx| tx.unbounded_send(value.clone()).is_ok());
+    }
+}
+
+/// What a driver woke up for.
+pub(crate) enum Wake<T> {
+    /// An event, or `None` once the subscription ended.
+    Event(Option<T>),
+    /// A tick, or `None` once the ticks ended.
+    Tick(Option<()>),
+}
+
+/// Waits for the next event or tick. Events take precedence, so events
+/// already received are applied before acting on a tick.
+pub(crate) async fn next_wake<T, S>(
+    events: &mut mpsc::UnboundedReceiver<T>,
+    ticks: &mut S,
+) -> Wake<T>
+where
+    S: Stream + Unpin,
+{
+    future::poll_fn(|cx| {
+        if let Poll::Ready(event) = events.poll_next_unpin(cx) {
+            return Poll::Ready(Wake::Event(event));
+        }
+        ticks
+            .poll_next_unpin(cx)
+            .map(|tick| Wake::Tick(tick.map(drop)))
+    })
+    .await
+}
+
+impl EventHandler {
+    pub fn new(profile: GameProfile) -> (Self, Events) {
+        let shared = Arc::new(Mutex::new(Shared {
+            profile,
+            subscribers: Subscribers::new(),
+        }));
+        let events = Events {
+            shared: shared.clone(),
+        };
+        (Self { shared }, events)
+    }
+}
+
+impl Events {
+    /// Returns a stream of the events received from now on. Dropping it
+    /// ends the subscription.
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<ServerEvent> {
+        self.shared.lock().unwrap().subscribers.subscribe()
+    }
+
+    /// Sets the dialect events are parsed in, such as once detected.
+    pub fn set_profile(&self, profile: GameProfile) {
+        self.shared.lock().unwrap().profile = profile;
+    }
+}
+
+impl Service<Request> for EventHandler {
+    type Error = Error;
+    type Response = Response;
+
+    type Future = Pin<Box<future::Ready<Result<Response, Error>>>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let mut shared = self.shared.lock().unwrap();
+        let event = ServerEvent::parse(&request.body, shared.profile)
+            .unwrap_or(ServerEvent::Other(request.body));
+        shared.subscribers.send(&event);
+        Box::pin(future::ok(Response::default()))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conn::testing::{FakeServer, TestExecutor};
+    use crate::conn::{Body, ConnectionBuilder, Role};
+    use crate::types::TeamId;
+
+    #[test]
+    fn subscribe_test() {
+        let exec = TestExecutor::new();
+        let (handler, events) = EventHandler::new(GameProfile::Bf4);
+        let mut first = events.subscribe();
+        let mut second = events.subscribe();
+        let dropped = events.subscribe();
+        drop(dropped);
+
+        let mut server = FakeServer::new(|_| None);
+        server.send_event(Body::new(vec!["server.onRoundOver", "2"]).unwrap());
+        server.send_event(Body::new(vec!["player.onJoin", "avitex"]).unwrap());
+        let _conn = ConnectionBuilder::new()
+            .handler(handler)
+            .with_transport_and_exec(server, Role::Client, exec.clone())
+            .unwrap();
+
+        let over = ServerEvent::RoundOver {
+            winning_team: TeamId::new(2).unwrap(),
+        };
+        assert_eq!(exec.block_on(first.next()), Some(over.clone()));
+        assert_eq!(exec.block_on(second.next()), Some(over));
+        // BF4 joins carry a GUID.
+        match exec.block_on(first.next()) {
+            Some(ServerEvent::Other(body)) => assert_eq!(body.len(), 2),
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert_eq!(events.shared.lock().unwrap().subscribers.senders.len(), 2);
+    }
+}
@@ -0,0 +1,240 @@
+use super::{request, Client, CommandError};
+use crate::conn::Word;
+use crate::types::{MapIndices, MapList, MapListItem, TeamId};
+
+/// The most maps `mapList.list` returns per call.
+pub const MAP_LIST_PAGE_SIZE: usize = 100;
+
+/// A single change to the map list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapListOp {
+    /// Removes the map at the index.
+    Remove(usize),
+    /// Inserts a map at the index.
+    Add { index: usize, item: MapListItem },
+}
+
+/// Plans the changes turning the current map list into the target.
+///
+/// Maps are compared by name, game mode and rounds. The longest run of
+/// maps the lists have in common, in order, is kept, and the others are
+/// removed or added, so reordering a map is a removal followed by an
+/// addition. Operations are to be applied in the order returned.
+pub fn plan_rotation(current: &MapList, target: &MapList) -> Vec<MapListOp> {
+    let (current, target) = (&current.maps, &target.maps);
+    let (n, m) = (current.len(), target.len());
+    // lcs[i][j] is the length of the longest common subsequence of
+    // current[i..] and target[j..].
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if same_map(&current[i], &target[j]) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut kept_current, mut kept_target) = (vec![false; n], vec![false; m]);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if same_map(&current[i], &target[j]) {
+            kept_current[i] = true;
+            kept_target[j] = true;
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    // Removing from the back keeps the remaining indices valid, leaving
+    // the kept maps in order, and the additions then fill in the gaps.
+    let removals = (0..n)
+        .rev()
+        .filter(|&i| !kept_current[i])
+        .map(MapListOp::Remove);
+    let additions = (0..m).filter(|&j| !kept_target[j]).map(|j| MapListOp::Add {
+        index: j,
+        item: target[j].clone(),
+    });
+    removals.chain(additions).collect()
+}
+
+fn same_map(a: &MapListItem, b: &MapListItem) -> bool {
+    a.map_name == b.map_name && a.game_mode == b.game_mode && a.rounds == b.rounds
+}
+
+impl Client {
+    /// Lists the maps in the rotation, requesting a page at a time.
+    pub async fn map_list(&mut self) -> Result<MapList, CommandError> {
+        let mut list = MapList::default();
+        loop {
+            let mut body = request("mapList.list");
+            if !list.maps.is_empty() {
+                body.push(&list.maps.len());
+            }
+            let page: MapList = self.query(body).await?;
+            let done = page.maps.len() < MAP_LIST_PAGE_SIZE;
+            list.maps.extend(page.maps);
+            if done {
+                return Ok(list);
+            }
+        }
+    }
+
+    /// Adds a map to the end of the rotation, or at the index.
+    ///
+    /// The server checks the map can be played in the game mode. BF4
+    /// maps can be checked beforehand with
+    /// [`crate::catalogue::validate_map_mode`].
+    pub async fn map_list_add(
+        &mut self,
+        item: &MapListItem,
+        index: Option<usize>,
+    ) -> Result<(), CommandError> {
+        let mut body = request("mapList.add");
+        body.push(&Word::new(&item.map_name)?);
+        body.push(&Word::new(&item.game_mode)?);
+        body.push(&item.rounds);
+        if let Some(index) = index {
+            body.push(&index);
+        }
+        self.command(body).await.map(drop)
+    }
+
+    /// Removes the map at the index from the rotation.
+    pub async fn map_list_remove(&mut self, index: usize) -> Result<(), CommandError> {
+        let mut body = request("mapList.remove");
+        body.push(&index);
+        self.command(body).await.map(drop)
+    }
+
+    /// Removes all maps from the rotation.
+    pub async fn map_list_clear(&mut self) -> Result<(), CommandError> {
+        self.command(request("mapList.clear")).await.map(drop)
+    }
+
+    /// Sets the map to be played after the current round.
+    pub async fn map_list_set_next_map_index(&mut self, index: usize) -> Result<(), CommandError> {
+        let mut body = request("mapList.setNextMapIndex");
+        body.push(&index);
+        self.command(body).await.map(drop)
+    }
+
+    /// Returns the indices of the current and next map.
+    pub async fn map_list_get_map_indices(&mut self) -> Result<MapIndices, CommandError> {
+        self.query(request("mapList.getMapIndices")).await
+    }
+
+    /// Ends the current round and switches to the next round.
+    pub async fn map_list_run_next_round(&mut self) -> Result<(), CommandError> {
+        self.command(request("mapList.runNextRound"))
+            .await
+            .map(drop)
+    }
+
+    /// Restarts the current round.
+    pub async fn map_list_restart_round(&mut self) -> Result<(), CommandError> {
+        self.command(request("mapList.restartRound"))
+            .await
+            .map(drop)
+    }
+
+    /// Ends the current round, declaring the team as the winner.
+    pub async fn map_list_end_round(&mut self, winner: TeamId) -> Result<(), CommandError> {
+        let mut body = request("mapList.endRound");
+        body.push(&winner);
+        self.command(body).await.map(drop)
+    }
+
+    /// Saves the rotation to disk.
+    pub async fn map_list_save(&mut self) -> Result<(), CommandError> {
+        self.command(request("mapList.save")).await.map(drop)
+    }
+
+    /// Turns the current rotation into the target, returning the
+    /// changes made. See [`plan_rotation`] for how they are chosen.
+    ///
+    /// The changes are not saved to disk.
+    pub async fn apply_rotation(
+        &mut self,
+        target: &MapList,
+    ) -> Result<Vec<MapListOp>, CommandError> {
+        let current = self.map_list().await?;
+        let ops = plan_rotation(&current, target);
+        for op in &ops {
+            match op {
+                MapListOp::Remove(index) => self.map_list_remove(*index).await?,
+                MapListOp::Add { index, item } => self.map_list_add(item, Some(*index)).await?,
+            }
+        }
+        Ok(ops)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(maps: &[&str]) -> MapList {
+        let maps = maps
+            .iter()
+            .map(|map| MapListItem {
+                map_name: map.to_string(),
+                game_mode: "ConquestLarge0".to_string(),
+                rounds: 2,
+                words: Vec::new(),
+            })
+            .collect();
+        MapList { maps }
+    }
+
+    fn apply(current: &MapList, ops: &[MapListOp]) -> MapList {
+        let mut maps = current.maps.clone();
+        for op in ops {
+            match op {
+                MapListOp::Remove(index) => {
+                    maps.remove(*index);
+                }
+                MapListOp::Add { index, item } => maps.insert(*index, item.clone()),
+            }
+        }
+        MapList { maps }
+    }
+
+    #[test]
+    fn plan_rotation_test() {
+        let current = list(&["A", "B", "C", "D"]);
+
+        assert!(plan_rotation(&current, &current).is_empty());
+
+        let target = list(&["A", "C", "E", "D"]);
+        let ops = plan_rotation(&current, &target);
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[0], MapListOp::Remove(1));
+        assert_eq!(apply(&current, &ops), target);
+
+        // Moving a map is a removal and an addition.
+        let target = list(&["D", "A", "B", "C"]);
+        let ops = plan_rotation(&current, &target);
+        assert_eq!(ops.len(), 2);
+        assert_eq!(apply(&current, &ops), target);
+
+        let target = list(&[]);
+        assert_eq!(apply(&current, &plan_rotation(&current, &target)), target);
+        assert_eq!(apply(&target, &plan_rotation(&target, &current)), current);
+
+        // A change of rounds replaces the map.
+        let mut target = current.clone();
+        target.maps[2].rounds = 1;
+        let ops = plan_rotation(&current, &target);
+        assert_eq!(ops.len(), 2);
+        assert_eq!(apply(&current, &ops), target);
+    }
+}
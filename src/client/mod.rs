@@ -0,0 +1,357 @@
+//! A typed client for the everyday admin commands.
+
+mod ban_list;
+mod error;
+mod events;
+mod map_list;
+mod name_list;
+mod punkbuster;
+mod roster;
+mod round;
+mod squad;
+mod vars;
+
+use std::time::Duration;
+
+use crate::conn::{Body, BodyError, Connection, FromWords, Word};
+use crate::profile::GameProfile;
+use crate::types::{PlayerList, PlayerName, PlayerSubset, ServerInfo, SquadId, TeamId, Version};
+
+pub use self::ban_list::BAN_LIST_PAGE_SIZE;
+pub use self::error::CommandError;
+pub use self::events::{EventHandler, Events};
+pub use self::map_list::{plan_rotation, MapListOp, MAP_LIST_PAGE_SIZE};
+pub use self::name_list::{NameList, NameListKind, NameListSync, NAME_LIST_PAGE_SIZE};
+pub use self::roster::{RosterChanges, RosterDriver};
+pub use self::round::{RoundDriver, RoundEvents};
+
+const STATUS_OK: &str = "OK";
+
+/// Sends typed commands over a connection, checking the response
+/// status and decoding the response words.
+///
+/// Commands are sent in the dialect of the client's game profile, and
+/// commands the game does not know are rejected before sending.
+pub struct Client {
+    conn: Connection,
+    profile: GameProfile,
+}
+
+impl Client {
+    /// Creates a client speaking the default dialect, BF4.
+    pub fn new(conn: Connection) -> Self {
+        Self::with_profile(conn, GameProfile::default())
+    }
+
+    pub fn with_profile(conn: Connection, profile: GameProfile) -> Self {
+        Self { conn, profile }
+    }
+
+    /// Creates a client speaking the dialect of the game reported by
+    /// the server's `version` response.
+    pub async fn detect(conn: Connection) -> Result<Self, CommandError> {
+        let mut client = Self::new(conn);
+        client.detect_profile().await?;
+        Ok(client)
+    }
+
+    /// Selects the profile of the game reported by the server's
+    /// `version` response, returning it.
+    pub async fn detect_profile(&mut self) -> Result<GameProfile, CommandError> {
+        let version = self.version().await?;
+        self.profile =
+            GameProfile::from_version(&version).ok_or(CommandError::UnknownGame(version.game))?;
+        Ok(self.profile)
+    }
+
+    pub fn profile(&self) -> GameProfile {
+        self.profile
+    }
+
+    pub fn set_profile(&mut self, profile: GameProfile) {
+        self.profile = profile;
+    }
+
+    /// Returns a mutable reference to the underlying connection.
+    pub fn get_mut(&mut self) -> &mut Connection {
+        &mut self.conn
+    }
+
+    /// Consumes the client, returning the underlying connection.
+    pub fn into_inner(self) -> Connection {
+        self.conn
+    }
+
+    /// Sends a request, returning the response if its status is `OK`.
+    pub async fn command(&mut self, body: Body) -> Result<Body, CommandError> {
+        if let Some(command) = body.words().first() {
+            if !self.profile.supports(command.as_str()) {
+                return Err(CommandError::Unsupported {
+                    command: command.as_str().to_string(),
+                    profile: self.profile,
+                });
+            }
+        }
+        check_status(self.conn.send_body(body).await?)
+    }
+
+    /// Sends a request, decoding the words following the status.
+    pub async fn query<T: FromWords>(&mut self, body: Body) -> Result<T, CommandError> {
+        Ok(self.command(body).await?.decode(1)?)
+    }
+
+    /// Sends a message to the players in the subset.
+    pub async fn say(&mut self, message: &str, subset: &PlayerSubset) -> Result<(), CommandError> {
+        let mut body = request("admin.say");
+        body.push(&Word::new(message)?);
+        body.push_words(subset);
+        self.command(body).await.map(drop)
+    }
+
+    /// Shows a message prominently to the players in the subset,
+    /// optionally for a duration rather than the server default.
+    /// See [`GameProfile::yell_duration`].
+    pub async fn yell(
+        &mut self,
+        message: &str,
+        duration: Option<Duration>,
+        subset: &PlayerSubset,
+    ) -> Result<(), CommandError> {
+        let mut body = request("admin.yell");
+        body.push(&Word::new(message)?);
+        body.push(&self.profile.yell_duration(duration));
+        body.push_words(subset);
+        self.command(body).await.map(drop)
+    }
+
+    /// Kicks a player, with an optional reason shown to them.
+    pub async fn kick_player(
+        &mut self,
+        name: &PlayerName,
+        reason: Option<&str>,
+    ) -> Result<(), CommandError> {
+        let mut body = request("admin.kickPlayer");
+        body.push(name);
+        if let Some(reason) = reason {
+            body.push(&Word::new(reason)?);
+        }
+        self.command(body).await.map(drop)
+    }
+
+    /// Kills a player, without any scoring effects.
+    pub async fn kill_player(&mut self, name: &PlayerName) -> Result<(), CommandError> {
+        let mut body = request("admin.killPlayer");
+        body.push(name);
+        self.command(body).await.map(drop)
+    }
+
+    /// Moves a player to a team and squad. Unless forced, only dead
+    /// players can be moved.
+    pub async fn move_player(
+        &mut self,
+        name: &PlayerName,
+        team_id: TeamId,
+        squad_id: SquadId,
+        force_kill: bool,
+    ) -> Result<(), CommandError> {
+        let mut body = request("admin.movePlayer");
+        body.push(name);
+        body.push(&team_id);
+        body.push(&squad_id);
+        body.push(&force_kill);
+        self.command(body).await.map(drop)
+    }
+
+    /// Lists the players in the subset.
+    pub async fn list_players(
+        &mut self,
+        subset: &PlayerSubset,
+    ) -> Result<PlayerList, CommandError> {
+        let mut body = request("admin.listPlayers");
+        body.push_words(subset);
+        self.query(body).await
+    }
+
+    pub async fn server_info(&mut self) -> Result<ServerInfo, CommandError> {
+        self.query(request("serverInfo")).await
+    }
+
+    pub async fn version(&mut self) -> Result<Version, CommandError> {
+        self.query(request("version")).await
+    }
+
+    /// Returns the name of the current level.
+    pub async fn current_level(&mut self) -> Result<String, CommandError> {
+        let response = self.command(request("currentLevel")).await?;
+        Ok(response.get(1)?)
+    }
+
+    /// Returns the ping of a player, in milliseconds.
+    pub async fn player_ping(&mut self, name: &PlayerName) -> Result<u32, CommandError> {
+        let response = self.command(player_request("player.ping", name)).await?;
+        Ok(response.get(1)?)
+    }
+
+    /// Returns how long a player has been idle.
+    pub async fn player_idle_duration(
+        &mut self,
+        name: &PlayerName,
+    ) -> Result<Duration, CommandError> {
+        let response = self
+            .command(player_request("player.idleDuration", name))
+            .await?;
+        let secs: f64 = response.get(1)?;
+        if !secs.is_finite() || secs < 0.0 {
+            return Err(BodyError::InvalidWord {
+                index: 1,
+                expected: "duration",
+            }
+            .into());
+        }
+        Ok(Duration::from_secs_f64(secs))
+    }
+
+    /// Returns whether a player is alive.
+    pub async fn player_is_alive(&mut self, name: &PlayerName) -> Result<bool, CommandError> {
+        let response = self.command(player_request("player.isAlive", name)).await?;
+        Ok(response.get(1)?)
+    }
+}
+
+impl From<Connection> for Client {
+    fn from(conn: Connection) -> Self {
+        Self::new(conn)
+    }
+}
+
+fn request(command: &str) -> Body {
+    Body::from(vec![Word::new(command).unwrap()])
+}
+
+fn player_request(command: &'static str, name: &PlayerName) -> Body {
+    let mut body = request(command);
+    body.push(name);
+    body
+}
+
+fn check_status(response: Body) -> Result<Body, CommandError> {
+    match response.words().first().map(Word::as_str) {
+        Some(STATUS_OK) => Ok(response),
+        status => Err(CommandError::Response {
+            status: status.unwrap_or_default().to_string(),
+        }),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conn::testing::{FakeServer, TestExecutor};
+    use crate::conn::{ConnectionBuilder, Role};
+
+    fn respond(request: &Body) -> Option<Body> {
+        let words: Vec<&str> = request.words().iter().map(Word::as_str).collect();
+        let response = match words[..] {
+            ["version"] => vec!["OK", "BF3", "1149977"],
+            ["admin.kickPlayer", "nobody", ..] => vec!["PlayerNotFound"],
+            ["admin.listPlayers", "all"] => vec![
+                "OK", "9", "name", "guid", "teamId", "squadId", "kills", "deaths", "score", "rank",
+                "ping", "1", "avitex", "", "1", "2", "10", "4", "1200", "42", "35",
+            ],
+            ["serverInfo"] => vec![
+                "OK",
+                "My Server",
+                "10",
+                "64",
+                "ConquestLarge0",
+                "MP_Prison",
+                "0",
+                "2",
+                "2",
+                "800",
+                "734",
+                "0",
+            ],
+            _ => vec!["OK"],
+        };
+        Some(Body::new(response).unwrap())
+    }
+
+    #[test]
+    fn commands_test() {
+        let exec = TestExecutor::new();
+        let server = FakeServer::new(respond);
+        let requests = server.requests();
+        let conn = ConnectionBuilder::new()
+            .with_transport_and_exec(server, Role::Client, exec.clone())
+            .unwrap();
+        let avitex = PlayerName::new("avitex").unwrap();
+
+        exec.block_on(async {
+            let mut client = Client::detect(conn).await.unwrap();
+            assert_eq!(client.profile(), GameProfile::Bf3);
+
+            client.say("hello", &PlayerSubset::All).await.unwrap();
+            let team = PlayerSubset::Team(TeamId::new(1).unwrap());
+            client.yell("hi", None, &team).await.unwrap();
+            client.kick_player(&avitex, Some("afk")).await.unwrap();
+            let nobody = PlayerName::new("nobody").unwrap();
+            let err = client.kick_player(&nobody, None).await.unwrap_err();
+            assert_eq!(err.status(), Some("PlayerNotFound"));
+
+            let list = client.list_players(&PlayerSubset::All).await.unwrap();
+            assert_eq!(list.players[0].name, avitex);
+            assert_eq!(list.players[0].rank, Some(42));
+            assert_eq!(list.players[0].player_type, None);
+
+            let info = client.server_info().await.unwrap();
+            assert_eq!(info.map, "MP_Prison");
+            assert_eq!(info.scores.score, [800, 734]);
+
+            // Commands BF3 does not know are not sent.
+            match client.current_level().await {
+                Err(CommandError::Unsupported { command, .. }) => {
+                    assert_eq!(command, "currentLevel")
+                }
+                other => panic!("unexpected result {:?}", other),
+            }
+        });
+
+        let requests: Vec<Vec<String>> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|body| {
+                body.words()
+                    .iter()
+                    .map(|w| w.as_str().to_string())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            requests,
+            [
+                vec!["version"],
+                vec!["admin.say", "hello", "all"],
+                vec!["admin.yell", "hi", "10", "team", "1"],
+                vec!["admin.kickPlayer", "avitex", "afk"],
+                vec!["admin.kickPlayer", "nobody"],
+                vec!["admin.listPlayers", "all"],
+                vec!["serverInfo"],
+            ]
+        );
+    }
+
+    #[test]
+    fn check_status_test() {
+        let ok = Body::new(vec!["OK", "BF4", "1"]).unwrap();
+        assert_eq!(check_status(ok.clone()).unwrap().words(), ok.words());
+
+        let err = check_status(Body::new(vec!["PlayerNotFound"]).unwrap()).unwrap_err();
+        assert_eq!(err.status(), Some("PlayerNotFound"));
+        let err = check_status(Body::from(Vec::new())).unwrap_err();
+        assert_eq!(err.status(), Some(""));
+    }
+}
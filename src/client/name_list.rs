@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+
+use super::{request, Client, CommandError};
+use crate::conn::{Body, BodyError, FromWords, WordReader};
+use crate::types::PlayerName;
+
+/// The most names a list command returns per call.
+pub const NAME_LIST_PAGE_SIZE: usize = 100;
+
+/// The server-side lists of player names sharing the same commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NameListKind {
+    /// Players allowed to join a full server, `reservedSlotsList.*`
+    ReservedSlots,
+    /// Players allowed to join as spectators, `spectatorList.*`
+    Spectators,
+    /// Players with in-game admin rights, `gameAdmin.*`
+    GameAdmins,
+}
+
+impl NameListKind {
+    /// Returns the prefix of the list's commands.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            NameListKind::ReservedSlots => "reservedSlotsList",
+            NameListKind::Spectators => "spectatorList",
+            NameListKind::GameAdmins => "gameAdmin",
+        }
+    }
+
+    fn request(self, command: &str) -> Body {
+        request(&format!("{}.{}", self.prefix(), command))
+    }
+}
+
+/// The changes made to a list to match a desired list.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NameListSync {
+    /// Names added, in the order of the desired list
+    pub added: Vec<PlayerName>,
+    /// Names removed, in the order of the current list
+    pub removed: Vec<PlayerName>,
+}
+
+impl NameListSync {
+    /// Computes the names to add and remove to turn the current list
+    /// into the desired one, ignoring order and duplicates.
+    pub fn diff(current: &[PlayerName], desired: &[PlayerName]) -> Self {
+        let current_set: HashSet<&PlayerName> = current.iter().collect();
+        let desired_set: HashSet<&PlayerName> = desired.iter().collect();
+        let mut seen = HashSet::new();
+        let added = desired
+            .iter()
+            .filter(|name| !current_set.contains(name) && seen.insert(*name))
+            .cloned()
+            .collect();
+        let mut seen = HashSet::new();
+        let removed = current
+            .iter()
+            .filter(|name| !desired_set.contains(name) && seen.insert(*name))
+            .cloned()
+            .collect();
+        Self { added, removed }
+    }
+
+    /// Returns `true` if no changes were needed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// A page of names, which follow one another without a count prefix.
+struct NamePage(Vec<PlayerName>);
+
+impl FromWords for NamePage {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let mut names = Vec::new();
+        while !reader.is_empty() {
+            names.push(reader.parse()?);
+        }
+        Ok(NamePage(names))
+    }
+}
+
+/// Commands on one of the server's lists of player names.
+///
+/// Changes are made in memory, and only persisted once saved.
+pub struct NameList<'a> {
+    client: &'a mut Client,
+    kind: NameListKind,
+}
+
+impl<'a> NameList<'a> {
+    pub fn kind(&self) -> NameListKind {
+        self.kind
+    }
+
+    pub async fn add(&mut self, name: &PlayerName) -> Result<(), CommandError> {
+        let mut body = self.kind.request("add");
+        body.push(name);
+        self.client.command(body).await.map(drop)
+    }
+
+    pub async fn remove(&mut self, name: &PlayerName) -> Result<(), CommandError> {
+        let mut body = self.kind.request("remove");
+        body.push(name);
+        self.client.command(body).await.map(drop)
+    }
+
+    pub async fn clear(&mut self) -> Result<(), CommandError> {
+        self.client
+            .command(self.kind.request("clear"))
+            .await
+            .map(drop)
+    }
+
+    /// Lists all names, requesting a page at a time.
+    pub async fn list(&mut self) -> Result<Vec<PlayerName>, CommandError> {
+        let mut names = Vec::new();
+        loop {
+            let mut body = self.kind.request("list");
+            if !names.is_empty() {
+                body.push(&names.len());
+            }
+            let NamePage(page) = self.client.query(body).await?;
+            let done = page.len() < NAME_LIST_PAGE_SIZE;
+            names.extend(page);
+            if done {
+                return Ok(names);
+            }
+        }
+    }
+
+    /// Saves the list to disk.
+    pub async fn save(&mut self) -> Result<(), CommandError> {
+        self.client
+            .command(self.kind.request("save"))
+            .await
+            .map(drop)
+    }
+
+    /// Loads the list from disk, replacing the current list.
+    pub async fn load(&mut self) -> Result<(), CommandError> {
+        self.client
+            .command(self.kind.request("load"))
+            .await
+            .map(drop)
+    }
+
+    /// Adds and removes names so the list matches the desired names,
+    /// returning the changes made. The list is not saved.
+    pub async fn sync(&mut self, desired: &[PlayerName]) -> Result<NameListSync, CommandError> {
+        let current = self.list().await?;
+        let sync = NameListSync::diff(&current, desired);
+        for name in &sync.removed {
+            self.remove(name).await?;
+        }
+        for name in &sync.added {
+            self.add(name).await?;
+        }
+        Ok(sync)
+    }
+}
+
+impl Client {
+    /// Returns the commands on one of the lists of player names.
+    pub fn name_list(&mut self, kind: NameListKind) -> NameList<'_> {
+        NameList { client: self, kind }
+    }
+
+    /// Returns whether players with reserved slots may kick others to
+    /// join a full server.
+    pub async fn aggressive_join(&mut self) -> Result<bool, CommandError> {
+        let response = self
+            .command(request("reservedSlotsList.aggressiveJoin"))
+            .await?;
+        Ok(response.get(1)?)
+    }
+
+    pub async fn set_aggressive_join(&mut self, enabled: bool) -> Result<(), CommandError> {
+        let mut body = request("reservedSlotsList.aggressiveJoin");
+        body.push(&enabled);
+        self.command(body).await.map(drop)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<PlayerName> {
+        names.iter().map(|n| PlayerName::new(n).unwrap()).collect()
+    }
+
+    #[test]
+    fn diff_test() {
+        let current = names(&["a", "b", "c"]);
+        assert!(NameListSync::diff(&current, &names(&["c", "a", "b"])).is_empty());
+
+        let sync = NameListSync::diff(&current, &names(&["d", "b", "d", "e"]));
+        assert_eq!(sync.added, names(&["d", "e"]));
+        assert_eq!(sync.removed, names(&["a", "c"]));
+
+        let sync = NameListSync::diff(&current, &[]);
+        assert_eq!(sync.removed, current);
+    }
+}
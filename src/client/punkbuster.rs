@@ -0,0 +1,23 @@
+use super::{request, Client, CommandError};
+use crate::conn::Word;
+use crate::punkbuster::{PlistCorrelator, PlistToken};
+
+impl Client {
+    /// Sends a command to PunkBuster. Its output arrives later as
+    /// `punkBuster.onMessage` events, see [`crate::punkbuster`].
+    pub async fn pb_sv_command(&mut self, command: &str) -> Result<(), CommandError> {
+        let mut body = request("punkBuster.pb_sv_command");
+        body.push(&Word::new(command)?);
+        self.command(body).await.map(drop)
+    }
+
+    /// Requests the PunkBuster player list, returning the token the
+    /// correlator will attach to the list once received.
+    pub async fn pb_sv_plist(
+        &mut self,
+        correlator: &mut PlistCorrelator,
+    ) -> Result<PlistToken, CommandError> {
+        self.pb_sv_command("pb_sv_plist").await?;
+        Ok(correlator.request())
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+
+use super::events::{next_wake, Subscribers, Wake};
+use super::{Client, CommandError, Events};
+use crate::event::ServerEvent;
+use crate::roster::{Roster, RosterChange, RosterSnapshot};
+use crate::types::PlayerSubset;
+
+impl Client {
+    /// Lists all players and reconciles the roster with them, returning
+    /// the changes found. Meant to be called periodically.
+    pub async fn reconcile_roster(
+        &mut self,
+        roster: &mut Roster,
+    ) -> Result<Vec<RosterChange>, CommandError> {
+        let requested_at = SystemTime::now();
+        let list = self.list_players(&PlayerSubset::All).await?;
+        Ok(roster.reconcile(&list, requested_at))
+    }
+}
+
+/// Keeps a [`Roster`] current from the events of a connection and a
+/// listing on every tick, sending the changes to each subscriber.
+pub struct RosterDriver {
+    roster: Roster,
+    events: mpsc::UnboundedReceiver<ServerEvent>,
+    shared: Arc<Mutex<RosterShared>>,
+}
+
+/// Subscribes to the changes of a [`RosterDriver`].
+#[derive(Clone)]
+pub struct RosterChanges {
+    shared: Arc<Mutex<RosterShared>>,
+}
+
+struct RosterShared {
+    snapshot: RosterSnapshot,
+    subscribers: Subscribers<RosterChange>,
+}
+
+impl RosterDriver {
+    /// Creates a driver applying the events received from now on.
+    pub fn new(events: &Events) -> (Self, RosterChanges) {
+        let roster = Roster::new();
+        let shared = Arc::new(Mutex::new(RosterShared {
+            snapshot: roster.snapshot(),
+            subscribers: Subscribers::new(),
+        }));
+        let changes = RosterChanges {
+            shared: shared.clone(),
+        };
+        let driver = Self {
+            roster,
+            events: events.subscribe(),
+            shared,
+        };
+        (driver, changes)
+    }
+
+    /// Drives the roster, reconciling it with a listing of all players on
+    /// every tick, such as of a `tokio::timer::Interval`. Reconcile
+    /// promptly after starting, as the players already on the server are
+    /// only known from listings.
+    ///
+    /// Returns once the events or ticks end, or a listing fails.
+    pub async fn run<S>(mut self, client: &mut Client, mut ticks: S) -> Result<(), CommandError>
+    where
+        S: Stream + Unpin,
+    {
+        loop {
+            let changes = match next_wake(&mut self.events, &mut ticks).await {
+                Wake::Event(Some(event)) => self.roster.apply_event(&event, SystemTime::now()),
+                Wake::Tick(Some(())) => client.reconcile_roster(&mut self.roster).await?,
+                Wake::Event(None) | Wake::Tick(None) => return Ok(()),
+            };
+            self.publish(changes);
+        }
+    }
+
+    fn publish(&mut self, changes: Vec<RosterChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        let mut shared = self.shared.lock().unwrap();
+        shared.snapshot = self.roster.snapshot();
+        for change in &changes {
+            shared.subscribers.send(change);
+        }
+    }
+}
+
+impl RosterChanges {
+    /// Returns a stream of the changes from now on. Dropping it ends the
+    /// subscription.
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<RosterChange> {
+        self.shared.lock().unwrap().subscribers.subscribe()
+    }
+
+    /// Returns the players, as of the last change.
+    pub fn snapshot(&self) -> RosterSnapshot {
+        self.shared.lock().unwrap().snapshot.clone()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use futures_util::future::{self, Either};
+    use futures_util::stream::{self, StreamExt};
+
+    use super::*;
+    use crate::client::EventHandler;
+    use crate::conn::testing::{FakeServer, TestExecutor};
+    use crate::conn::{Body, ConnectionBuilder, Role, Word};
+    use crate::profile::GameProfile;
+    use crate::types::PlayerName;
+
+    const AVITEX: &[&str] = &[
+        "8", "name", "guid", "teamId", "squadId", "kills", "deaths", "score", "rank", "1",
+        "avitex", "", "1", "2", "10", "4", "1200", "42",
+    ];
+
+    fn respond(request: &Body) -> Option<Body> {
+        let words: Vec<&str> = request.words().iter().map(Word::as_str).collect();
+        let mut response = vec!["OK"];
+        if words[..] == ["admin.listPlayers", "all"] {
+            response.extend_from_slice(AVITEX);
+        }
+        Some(Body::new(response).unwrap())
+    }
+
+    #[test]
+    fn driver_test() {
+        let exec = TestExecutor::new();
+        let (handler, events) = EventHandler::new(GameProfile::Bf3);
+        let (driver, changes) = RosterDriver::new(&events);
+        let mut first = changes.subscribe();
+        let mut second = changes.subscribe();
+
+        let mut server = FakeServer::new(respond);
+        server.send_event(Body::new(vec!["player.onAuthenticated", "bob"]).unwrap());
+        let mut leave = vec!["player.onLeave", "avitex"];
+        leave.extend_from_slice(AVITEX);
+        server.send_event(Body::new(leave).unwrap());
+        let conn = ConnectionBuilder::new()
+            .handler(handler)
+            .with_transport_and_exec(server, Role::Client, exec.clone())
+            .unwrap();
+        let mut client = Client::with_profile(conn, GameProfile::Bf3);
+
+        // The first tick is ready before any event arrives.
+        let ticks = stream::iter(vec![()]).chain(stream::pending());
+        let run = Box::pin(driver.run(&mut client, ticks));
+        let received = first.by_ref().take(3).collect::<Vec<_>>();
+        let received = match exec.block_on(future::select(run, received)) {
+            Either::Right((received, _)) => received,
+            Either::Left((res, _)) => panic!("driver stopped: {:?}", res),
+        };
+
+        let name = |name| PlayerName::new(name).unwrap();
+        assert_eq!(
+            received,
+            [
+                RosterChange::Joined(name("avitex")),
+                RosterChange::Joined(name("bob")),
+                RosterChange::Left(name("avitex")),
+            ]
+        );
+        assert_eq!(exec.block_on(second.next()), Some(received[0].clone()));
+        let snapshot = changes.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert!(snapshot.contains_key(&name("bob")));
+    }
+}
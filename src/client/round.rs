@@ -0,0 +1,195 @@
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use futures_channel::mpsc;
+use futures_core::Stream;
+
+use super::events::{next_wake, Subscribers, Wake};
+use super::{Client, CommandError, Events};
+use crate::event::ServerEvent;
+use crate::round::{Round, RoundEvent, RoundTracker};
+
+impl Client {
+    /// Polls the server info and applies it to the tracker, returning
+    /// the events of any missed level load. Meant to be called
+    /// periodically.
+    pub async fn poll_round(
+        &mut self,
+        tracker: &mut RoundTracker,
+    ) -> Result<Vec<RoundEvent>, CommandError> {
+        let info = self.server_info().await?;
+        Ok(tracker.apply_server_info(&info, SystemTime::now()))
+    }
+}
+
+/// Tracks rounds from the events of a connection and a `serverInfo`
+/// poll on every tick, sending the lifecycle events to each subscriber.
+pub struct RoundDriver {
+    tracker: RoundTracker,
+    events: mpsc::UnboundedReceiver<ServerEvent>,
+    shared: Arc<Mutex<RoundShared>>,
+}
+
+/// Subscribes to the lifecycle events of a [`RoundDriver`].
+#[derive(Clone)]
+pub struct RoundEvents {
+    shared: Arc<Mutex<RoundShared>>,
+}
+
+struct RoundShared {
+    round: Option<Round>,
+    subscribers: Subscribers<RoundEvent>,
+}
+
+impl RoundDriver {
+    /// Creates a driver applying the events received from now on.
+    pub fn new(events: &Events) -> (Self, RoundEvents) {
+        let shared = Arc::new(Mutex::new(RoundShared {
+            round: None,
+            subscribers: Subscribers::new(),
+        }));
+        let round_events = RoundEvents {
+            shared: shared.clone(),
+        };
+        let driver = Self {
+            tracker: RoundTracker::new(),
+            events: events.subscribe(),
+            shared,
+        };
+        (driver, round_events)
+    }
+
+    /// Drives the tracker, polling the server info on every tick, such
+    /// as of a `tokio::timer::Interval`. Poll promptly after starting, as
+    /// the round in progress is only picked up by a poll.
+    ///
+    /// Returns once the events or ticks end, or a poll fails.
+    pub async fn run<S>(mut self, client: &mut Client, mut ticks: S) -> Result<(), CommandError>
+    where
+        S: Stream + Unpin,
+    {
+        loop {
+            let round_events = match next_wake(&mut self.events, &mut ticks).await {
+                Wake::Event(Some(event)) => self.tracker.apply_event(&event, SystemTime::now()),
+                Wake::Tick(Some(())) => client.poll_round(&mut self.tracker).await?,
+                Wake::Event(None) | Wake::Tick(None) => return Ok(()),
+            };
+            self.publish(round_events);
+        }
+    }
+
+    fn publish(&mut self, round_events: Vec<RoundEvent>) {
+        let mut shared = self.shared.lock().unwrap();
+        // Polls sample the tickets, so the round may change without events.
+        shared.round = self.tracker.round().cloned();
+        for event in &round_events {
+            shared.subscribers.send(event);
+        }
+    }
+}
+
+impl RoundEvents {
+    /// Returns a stream of the lifecycle events from now on. Dropping it
+    /// ends the subscription.
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<RoundEvent> {
+        self.shared.lock().unwrap().subscribers.subscribe()
+    }
+
+    /// Returns the current round, or the last one between rounds.
+    pub fn round(&self) -> Option<Round> {
+        self.shared.lock().unwrap().round.clone()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use futures_util::future::{self, Either};
+    use futures_util::stream::{self, StreamExt};
+
+    use super::*;
+    use crate::client::EventHandler;
+    use crate::conn::testing::{FakeServer, TestExecutor};
+    use crate::conn::{Body, ConnectionBuilder, Role, Word};
+    use crate::profile::GameProfile;
+    use crate::types::TeamId;
+
+    fn respond(request: &Body) -> Option<Body> {
+        let words: Vec<&str> = request.words().iter().map(Word::as_str).collect();
+        let response = match words[..] {
+            ["serverInfo"] => vec![
+                "OK",
+                "My Server",
+                "10",
+                "64",
+                "ConquestLarge0",
+                "MP_Prison",
+                "0",
+                "2",
+                "2",
+                "800",
+                "734",
+                "0",
+            ],
+            _ => vec!["OK"],
+        };
+        Some(Body::new(response).unwrap())
+    }
+
+    #[test]
+    fn driver_test() {
+        let exec = TestExecutor::new();
+        let (handler, events) = EventHandler::new(GameProfile::Bf3);
+        let (driver, round_events) = RoundDriver::new(&events);
+        let mut first = round_events.subscribe();
+        let mut second = round_events.subscribe();
+
+        let mut server = FakeServer::new(respond);
+        server.send_event(Body::new(vec!["server.onRoundOver", "2"]).unwrap());
+        server.send_event(
+            Body::new(vec![
+                "server.onLevelLoaded",
+                "MP_Prison",
+                "ConquestLarge0",
+                "1",
+                "2",
+            ])
+            .unwrap(),
+        );
+        let conn = ConnectionBuilder::new()
+            .handler(handler)
+            .with_transport_and_exec(server, Role::Client, exec.clone())
+            .unwrap();
+        let mut client = Client::with_profile(conn, GameProfile::Bf3);
+
+        // The first tick is ready before any event arrives, picking up
+        // the round in progress.
+        let ticks = stream::iter(vec![()]).chain(stream::pending());
+        let run = Box::pin(driver.run(&mut client, ticks));
+        let received = first.by_ref().take(2).collect::<Vec<_>>();
+        let received = match exec.block_on(future::select(run, received)) {
+            Either::Right((received, _)) => received,
+            Either::Left((res, _)) => panic!("driver stopped: {:?}", res),
+        };
+
+        match &received[0] {
+            RoundEvent::RoundEnded { winner, scores, .. } => {
+                assert_eq!(*winner, TeamId::new(2).unwrap());
+                assert_eq!(scores.score, [800, 734]);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert_eq!(
+            received[1],
+            RoundEvent::RoundStarted {
+                map: "MP_Prison".to_string(),
+                game_mode: Some("ConquestLarge0".to_string()),
+                rounds_played: 1,
+                rounds_total: 2,
+            }
+        );
+        assert_eq!(exec.block_on(second.next()), Some(received[0].clone()));
+        assert_eq!(round_events.round().unwrap().rounds_played, 1);
+    }
+}
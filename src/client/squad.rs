@@ -0,0 +1,104 @@
+use super::{request, Client, CommandError};
+use crate::conn::{Body, BodyError, FromWord, FromWords, WordReader};
+use crate::types::{PlayerName, PlayerSubset, SquadId, SquadTree, TeamId};
+
+/// Values prefixed with their count.
+struct Counted<T>(Vec<T>);
+
+impl<T: FromWord> FromWords for Counted<T> {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let count: usize = reader.parse()?;
+        let mut values = Vec::with_capacity(count.min(reader.remaining().len()));
+        for _ in 0..count {
+            values.push(reader.parse()?);
+        }
+        Ok(Counted(values))
+    }
+}
+
+fn squad_request(command: &str, team_id: TeamId, squad_id: SquadId) -> Body {
+    let mut body = request(command);
+    body.push(&team_id);
+    body.push(&squad_id);
+    body
+}
+
+impl Client {
+    /// Lists the squads of a team with at least one player.
+    pub async fn squad_list_active(
+        &mut self,
+        team_id: TeamId,
+    ) -> Result<Vec<SquadId>, CommandError> {
+        let mut body = request("squad.listActive");
+        body.push(&team_id);
+        let Counted(squads) = self.query(body).await?;
+        Ok(squads)
+    }
+
+    /// Lists the names of the players in a squad.
+    pub async fn squad_list_players(
+        &mut self,
+        team_id: TeamId,
+        squad_id: SquadId,
+    ) -> Result<Vec<PlayerName>, CommandError> {
+        let body = squad_request("squad.listPlayers", team_id, squad_id);
+        let Counted(names) = self.query(body).await?;
+        Ok(names)
+    }
+
+    /// Returns the leader of a squad, if any.
+    pub async fn squad_leader(
+        &mut self,
+        team_id: TeamId,
+        squad_id: SquadId,
+    ) -> Result<Option<PlayerName>, CommandError> {
+        let body = squad_request("squad.leader", team_id, squad_id);
+        let response = self.command(body).await?;
+        // Squads without a leader may omit the name, or leave it empty.
+        if response.len() < 2 {
+            return Ok(None);
+        }
+        Ok(response.get(1)?)
+    }
+
+    /// Makes a player the leader of their squad.
+    pub async fn set_squad_leader(
+        &mut self,
+        team_id: TeamId,
+        squad_id: SquadId,
+        name: &PlayerName,
+    ) -> Result<(), CommandError> {
+        let mut body = squad_request("squad.leader", team_id, squad_id);
+        body.push(name);
+        self.command(body).await.map(drop)
+    }
+
+    /// Returns whether a squad is private.
+    pub async fn squad_private(
+        &mut self,
+        team_id: TeamId,
+        squad_id: SquadId,
+    ) -> Result<bool, CommandError> {
+        let body = squad_request("squad.private", team_id, squad_id);
+        Ok(self.command(body).await?.get(1)?)
+    }
+
+    /// Makes a squad private, or opens it to everyone.
+    pub async fn set_squad_private(
+        &mut self,
+        team_id: TeamId,
+        squad_id: SquadId,
+        private: bool,
+    ) -> Result<(), CommandError> {
+        let mut body = squad_request("squad.private", team_id, squad_id);
+        body.push(&private);
+        self.command(body).await.map(drop)
+    }
+
+    /// Lists all players, grouped by team and squad.
+    pub async fn squad_tree(&mut self) -> Result<SquadTree, CommandError> {
+        self.list_players(&PlayerSubset::All)
+            .await
+            .map(SquadTree::from)
+    }
+}
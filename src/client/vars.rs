@@ -0,0 +1,77 @@
+use super::{request, Client, CommandError};
+use crate::types::vars::{var_spec, VarChange, VarError, VarSpec, VARS};
+use crate::types::{ServerConfig, VarValue};
+
+/// The status of servers not knowing a command, such as variables
+/// only present in another game.
+const STATUS_UNKNOWN_COMMAND: &str = "UnknownCommand";
+
+fn lookup(name: &str) -> Result<&'static VarSpec, CommandError> {
+    var_spec(name).ok_or_else(|| VarError::UnknownVar(name.to_string()).into())
+}
+
+impl Client {
+    /// Gets a server variable by name, without the `vars.` prefix.
+    pub async fn get_var(&mut self, name: &str) -> Result<VarValue, CommandError> {
+        let spec = lookup(name)?;
+        self.get_var_spec(spec).await
+    }
+
+    /// Sets a server variable by name, without the `vars.` prefix,
+    /// validating the value against the registry before sending.
+    pub async fn set_var<V>(&mut self, name: &str, value: V) -> Result<(), CommandError>
+    where
+        V: Into<VarValue>,
+    {
+        let spec = lookup(name)?;
+        self.set_var_spec(spec, &value.into()).await
+    }
+
+    /// Gets every variable in the registry known to the server.
+    pub async fn fetch_config(&mut self) -> Result<ServerConfig, CommandError> {
+        let mut config = ServerConfig::new();
+        for spec in VARS {
+            match self.get_var_spec(spec).await {
+                Ok(value) => {
+                    config.vars.insert(spec.name, value);
+                }
+                Err(ref err) if err.status() == Some(STATUS_UNKNOWN_COMMAND) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Sets the variables differing from the desired config, returning
+    /// the changes made. Startup variables are not set, see
+    /// [`ServerConfig::diff`].
+    pub async fn apply_config(
+        &mut self,
+        desired: &ServerConfig,
+    ) -> Result<Vec<VarChange>, CommandError> {
+        let current = self.fetch_config().await?;
+        let changes = current.diff(desired);
+        for change in &changes {
+            self.set_var_spec(change.spec, &change.to).await?;
+        }
+        Ok(changes)
+    }
+
+    async fn get_var_spec(&mut self, spec: &VarSpec) -> Result<VarValue, CommandError> {
+        let response = self.command(request(&spec.command())).await?;
+        let mut reader = response.reader();
+        reader.next_word()?;
+        Ok(spec.read_value(&mut reader)?)
+    }
+
+    async fn set_var_spec(&mut self, spec: &VarSpec, value: &VarValue) -> Result<(), CommandError> {
+        for arguments in spec.set_arguments(value)? {
+            let mut body = request(&spec.command());
+            for word in &arguments {
+                body.push(word);
+            }
+            self.command(body).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryInto;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use bytes::BytesMut;
+
+use super::protocol::Event;
+use super::{Body, BodyError, Error, Protocol, Request, Response, Role, SocketError};
+
+const READ_BUF_SIZE: usize = 4096;
+
+/// A blocking client connection over a `std::net::TcpStream`.
+///
+/// Requests sent by the server (events) are answered with the default
+/// `OK` response and queued until retrieved with `poll_event`.
+pub struct Connection {
+    stream: TcpStream,
+    protocol: Protocol,
+    events: VecDeque<Request>,
+    responses: HashMap<u32, Body>,
+}
+
+impl Connection {
+    /// Wraps an already connected stream.
+    pub fn new(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            protocol: Protocol::new(Role::Client),
+            events: VecDeque::new(),
+            responses: HashMap::new(),
+        }
+    }
+
+    /// Connects to a server.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        Ok(Self::new(TcpStream::connect(addr)?))
+    }
+
+    /// Logs in with a plain text password.
+    pub fn login(&mut self, password: &str) -> Result<Body, Error> {
+        self.send(vec!["login.plainText", password])
+    }
+
+    /// Enables or disables server events for this connection.
+    pub fn enable_events(&mut self, enabled: bool) -> Result<Body, Error> {
+        let enabled = if enabled { "true" } else { "false" };
+        self.send(vec!["admin.eventsEnabled", enabled])
+    }
+
+    /// Send a request, blocking until the response is received.
+    pub fn send<B>(&mut self, words: B) -> Result<Body, Error>
+    where
+        B: TryInto<Body, Error = BodyError>,
+    {
+        let body = words.try_into()?;
+        let seq = self.protocol.send_request(body)?;
+        self.flush_transmit()?;
+        loop {
+            if let Some(body) = self.responses.remove(&seq) {
+                return Ok(body);
+            }
+            self.read_packets()?;
+        }
+    }
+
+    /// Returns the next server event, waiting up to the timeout
+    /// for one to arrive. A timeout of `None` blocks indefinitely, and a
+    /// zero timeout only reads what has already been received.
+    pub fn poll_event(&mut self, timeout: Option<Duration>) -> Result<Option<Request>, Error> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            if let Some(request) = self.events.pop_front() {
+                return Ok(Some(request));
+            }
+            let timeout =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            match self.read_packets_within(timeout) {
+                Ok(()) => (),
+                Err(Error::Socket(SocketError::Io(ref err))) if is_timeout(err) => return Ok(None),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns the underlying stream.
+    pub fn get_ref(&self) -> &TcpStream {
+        &self.stream
+    }
+
+    fn read_packets(&mut self) -> Result<(), Error> {
+        self.read_packets_within(None)
+    }
+
+    fn read_packets_within(&mut self, timeout: Option<Duration>) -> Result<(), Error> {
+        let mut read_buf = [0u8; READ_BUF_SIZE];
+        let read_len = self.read_within(&mut read_buf[..], timeout)?;
+        if read_len == 0 {
+            return Err(SocketError::Closed.into());
+        }
+        self.protocol.receive_bytes(&read_buf[..read_len])?;
+        while let Some(event) = self.protocol.poll_event() {
+            match event {
+                Event::Request { seq, body } => {
+                    self.protocol.respond(seq, Response::default().body)?;
+                    self.events.push_back(Request { body });
+                }
+                Event::Response { seq, body } => {
+                    self.responses.insert(seq, body);
+                }
+            }
+        }
+        self.flush_transmit()
+    }
+
+    /// Reads from the stream, failing with a timeout error if nothing
+    /// arrives in time. The socket options are restored before returning.
+    fn read_within(&mut self, buf: &mut [u8], timeout: Option<Duration>) -> io::Result<usize> {
+        match timeout {
+            None => self.stream.read(buf),
+            Some(timeout) if timeout == Duration::ZERO => {
+                // A zero read timeout is rejected, so poll without blocking.
+                self.stream.set_nonblocking(true)?;
+                let read_res = self.stream.read(buf);
+                self.stream.set_nonblocking(false)?;
+                read_res
+            }
+            Some(timeout) => {
+                self.stream.set_read_timeout(Some(timeout))?;
+                let read_res = self.stream.read(buf);
+                self.stream.set_read_timeout(None)?;
+                read_res
+            }
+        }
+    }
+
+    fn flush_transmit(&mut self) -> Result<(), Error> {
+        let mut write_buf = BytesMut::new();
+        self.protocol.write_transmit(&mut write_buf)?;
+        if !write_buf.is_empty() {
+            self.stream.write_all(&write_buf[..])?;
+        }
+        Ok(())
+    }
+}
+
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+    use crate::conn::packet::{read_packet, write_packet};
+    use crate::conn::{Packet, PacketKind, PacketSequence, Word};
+
+    /// The server end of a loopback connection.
+    struct Server {
+        stream: TcpStream,
+        read_buf: BytesMut,
+        next_seq: u32,
+    }
+
+    impl Server {
+        fn read(&mut self) -> Packet {
+            loop {
+                if let Some(packet) = read_packet(&mut self.read_buf).unwrap() {
+                    return packet;
+                }
+                let mut buf = [0u8; READ_BUF_SIZE];
+                let len = self.stream.read(&mut buf[..]).unwrap();
+                assert!(len > 0, "client closed the connection");
+                self.read_buf.extend_from_slice(&buf[..len]);
+            }
+        }
+
+        fn write(&mut self, kind: PacketKind, origin: Role, num: u32, words: &[&str]) -> Vec<u8> {
+            let seq = PacketSequence::new(kind, origin, num).unwrap();
+            let words = words.iter().map(|w| Word::new(w).unwrap()).collect();
+            let mut bytes = BytesMut::new();
+            write_packet(&mut bytes, Packet::new(seq, words)).unwrap();
+            bytes.to_vec()
+        }
+
+        fn respond(&mut self, request: &Packet, words: &[&str]) {
+            let bytes = self.write(
+                PacketKind::Response,
+                Role::Client,
+                request.seq.number(),
+                words,
+            );
+            self.stream.write_all(&bytes[..]).unwrap();
+        }
+
+        fn event_bytes(&mut self, words: &[&str]) -> Vec<u8> {
+            self.next_seq += 1;
+            self.write(PacketKind::Request, Role::Server, self.next_seq, words)
+        }
+
+        /// Reads the response of the client to an event.
+        fn read_ok(&mut self) {
+            let packet = self.read();
+            assert_eq!(packet.seq.kind(), PacketKind::Response);
+            assert_eq!(packet.seq.origin(), Role::Server);
+            assert_eq!(packet.words[0].as_str(), "OK");
+        }
+    }
+
+    fn loopback<F>(server: F) -> (Connection, thread::JoinHandle<()>)
+    where
+        F: FnOnce(Server) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            server(Server {
+                stream,
+                read_buf: BytesMut::new(),
+                next_seq: 0,
+            });
+        });
+        (Connection::connect(addr).unwrap(), handle)
+    }
+
+    fn words(body: &Body) -> Vec<&str> {
+        body.words().iter().map(Word::as_str).collect()
+    }
+
+    #[test]
+    fn login_test() {
+        let (mut conn, server) = loopback(|mut server| {
+            let request = server.read();
+            assert_eq!(request.seq.origin(), Role::Client);
+            assert_eq!(request.words[0].as_str(), "login.plainText");
+            assert_eq!(request.words[1].as_str(), "secret");
+            server.respond(&request, &["OK"]);
+        });
+        assert_eq!(words(&conn.login("secret").unwrap()), ["OK"]);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn request_response_test() {
+        let (mut conn, server) = loopback(|mut server| {
+            let request = server.read();
+            assert_eq!(request.words[0].as_str(), "serverInfo");
+            // An event arriving before the response is queued.
+            let event = server.event_bytes(&["player.onJoin", "avitex"]);
+            server.stream.write_all(&event[..]).unwrap();
+            server.read_ok();
+            server.respond(&request, &["OK", "Server"]);
+            let request = server.read();
+            assert_eq!(request.words[0].as_str(), "version");
+            server.respond(&request, &["OK", "BF4", "1"]);
+        });
+        assert_eq!(
+            words(&conn.send(vec!["serverInfo"]).unwrap()),
+            ["OK", "Server"]
+        );
+        assert_eq!(
+            words(&conn.send(vec!["version"]).unwrap()),
+            ["OK", "BF4", "1"]
+        );
+        let event = conn.poll_event(Some(Duration::ZERO)).unwrap().unwrap();
+        assert_eq!(words(&event.body), ["player.onJoin", "avitex"]);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn poll_event_test() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (mut conn, server) = loopback(move |mut server| {
+            let request = server.read();
+            server.respond(&request, &["OK"]);
+            rx.recv().unwrap();
+            // A partial event only completes after a later read.
+            let event = server.event_bytes(&["server.onRoundOver", "1"]);
+            server.stream.write_all(&event[..5]).unwrap();
+            thread::sleep(Duration::from_millis(50));
+            server.stream.write_all(&event[5..]).unwrap();
+            server.read_ok();
+            rx.recv().unwrap();
+            let event = server.event_bytes(&["player.onLeave", "avitex"]);
+            server.stream.write_all(&event[..]).unwrap();
+            server.read_ok();
+        });
+
+        assert!(conn.poll_event(Some(Duration::ZERO)).unwrap().is_none());
+        assert_eq!(words(&conn.enable_events(true).unwrap()), ["OK"]);
+        let event = conn.poll_event(Some(Duration::from_millis(20))).unwrap();
+        assert!(event.is_none());
+        tx.send(()).unwrap();
+        let event = conn.poll_event(Some(Duration::from_secs(5))).unwrap();
+        assert_eq!(words(&event.unwrap().body), ["server.onRoundOver", "1"]);
+        tx.send(()).unwrap();
+        let event = conn.poll_event(None).unwrap();
+        assert_eq!(words(&event.unwrap().body), ["player.onLeave", "avitex"]);
+        server.join().unwrap();
+    }
+}
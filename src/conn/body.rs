@@ -1,15 +1,41 @@
 use std::convert::{TryFrom, TryInto};
-use std::{str, fmt};
+use std::{fmt, str};
 
+use super::command_line::{self, CommandLineError};
 use super::packet;
+use super::{FromWord, FromWords, ToWord, ToWords, WordReader};
 use bytes::Bytes;
 
 #[derive(Debug, PartialEq)]
 pub enum BodyError {
     InvalidWordChar(u8),
+    /// There is no word at the index.
+    MissingWord(usize),
+    /// The word at the index is not a valid representation
+    /// of the expected type.
+    InvalidWord {
+        index: usize,
+        expected: &'static str,
+    },
 }
 
-#[derive(Debug, Clone)]
+impl fmt::Display for BodyError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BodyError::InvalidWordChar(c) => write!(fmt, "invalid word character {:#04x}", c),
+            BodyError::MissingWord(index) => write!(fmt, "missing word at index {}", index),
+            BodyError::InvalidWord { index, expected } => {
+                write!(
+                    fmt,
+                    "invalid word at index {}, expected {}",
+                    index, expected
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Body {
     content: Vec<Word>,
 }
@@ -30,10 +56,76 @@ impl Body {
         Ok(Self { content })
     }
 
+    /// Parses a shell-like command line into a body.
+    ///
+    /// Words are separated by whitespace, double quotes group characters
+    /// into a word (`""` being an empty word), and a backslash escapes
+    /// the following character.
+    pub fn parse_command_line(line: &str) -> Result<Self, CommandLineError> {
+        command_line::parse(line).map(Self::from)
+    }
+
+    /// Formats the body as a command line, which parses back into
+    /// the same body with `parse_command_line`.
+    pub fn to_command_line(&self) -> String {
+        command_line::format(&self.content)
+    }
+
     pub fn words(&self) -> &[Word] {
         self.content.as_ref()
     }
 
+    /// Returns the number of words in the body.
+    pub fn len(&self) -> usize {
+        self.content.len()
+    }
+
+    /// Returns `true` if the body contains no words.
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
+    /// Extracts the word at the index as a typed value.
+    pub fn get<T: FromWord>(&self, index: usize) -> Result<T, BodyError> {
+        let word = self
+            .content
+            .get(index)
+            .ok_or(BodyError::MissingWord(index))?;
+        T::from_word(word).ok_or(BodyError::InvalidWord {
+            index,
+            expected: T::EXPECTED,
+        })
+    }
+
+    /// Appends a typed value as a word.
+    pub fn push<T: ToWord + ?Sized>(&mut self, value: &T) {
+        self.content.push(value.to_word());
+    }
+
+    /// Appends a typed value spanning one or more words.
+    pub fn push_words<T: ToWords + ?Sized>(&mut self, value: &T) {
+        value.to_words(&mut self.content);
+    }
+
+    /// Returns a reader over the words of the body.
+    pub fn reader(&self) -> WordReader<'_> {
+        WordReader::new(&self.content)
+    }
+
+    /// Decodes a typed value from the words of the body, starting at
+    /// the index and requiring every following word to be consumed.
+    pub fn decode<T: FromWords>(&self, index: usize) -> Result<T, BodyError> {
+        let mut reader = self.reader();
+        for _ in 0..index {
+            reader.next_word()?;
+        }
+        let value = reader.decode()?;
+        if !reader.is_empty() {
+            return Err(reader.invalid(reader.index(), "end of body"));
+        }
+        Ok(value)
+    }
+
     pub fn to_vec(self) -> Vec<Word> {
         self.content
     }
@@ -41,14 +133,7 @@ impl Body {
 
 impl fmt::Display for Body {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let word_count = self.content.len();
-        for i in 0..word_count  {
-            write!(fmt, "{}", self.content[i].as_str())?;
-            if i < (word_count - 1) {
-                write!(fmt, "; ")?;
-            }
-        }
-        return Ok(())
+        fmt.write_str(&self.to_command_line())
     }
 }
 
@@ -93,7 +178,7 @@ impl From<Vec<Word>> for Body {
 ///////////////////////////////////////////////////////////////////////////////
 
 /// A unit of transmission.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Word {
     bytes: Bytes,
 }
@@ -101,7 +186,7 @@ pub struct Word {
 impl Word {
     /// Create a word from a UTF8 string.
     pub fn new(word: &str) -> Result<Self, BodyError> {
-        Self::from_bytes(Bytes::from(word.as_bytes()))
+        Self::from_bytes(Bytes::from(word.as_bytes().to_vec()))
     }
 
     /// Create a word from bytes.
@@ -160,6 +245,6 @@ impl TryFrom<&[u8]> for Word {
     type Error = BodyError;
 
     fn try_from(s: &[u8]) -> Result<Self, BodyError> {
-        Self::from_bytes(Bytes::from(s))
+        Self::from_bytes(Bytes::from(s.to_vec()))
     }
 }
@@ -0,0 +1,375 @@
+use serde::de::value::StrDeserializer;
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::Deserialize;
+
+use super::{Error, STRIDED};
+use crate::conn::{Body, BodyError, FromWord, Word};
+
+/// Deserializes a typed value from the words of a body.
+pub fn from_body<'de, T>(body: &'de Body) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    from_words(body.words())
+}
+
+/// Deserializes a typed value from words, failing if any words remain.
+pub fn from_words<'de, T>(words: &'de [Word]) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    let mut deserializer = Deserializer::new(words);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// A deserializer reading typed values from words.
+pub struct Deserializer<'de> {
+    words: &'de [Word],
+    index: usize,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Creates a deserializer reading from the first word.
+    pub fn new(words: &'de [Word]) -> Self {
+        Self { words, index: 0 }
+    }
+
+    /// Returns the index of the next word to be read.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the words yet to be read.
+    pub fn remaining(&self) -> &'de [Word] {
+        &self.words[self.index..]
+    }
+
+    /// Validates all words have been read.
+    pub fn end(&self) -> Result<(), Error> {
+        if self.index < self.words.len() {
+            Err(Error::TrailingWords(self.index))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn next_word(&mut self) -> Result<&'de Word, Error> {
+        let word = self
+            .words
+            .get(self.index)
+            .ok_or(BodyError::MissingWord(self.index))?;
+        self.index += 1;
+        Ok(word)
+    }
+
+    fn parse<T: FromWord>(&mut self) -> Result<T, Error> {
+        let index = self.index;
+        let word = self.next_word()?;
+        T::from_word(word).ok_or(Error::Body(BodyError::InvalidWord {
+            index,
+            expected: T::EXPECTED,
+        }))
+    }
+
+    fn parse_str(&mut self) -> Result<&'de str, Error> {
+        self.next_word().map(Word::as_str)
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    /// The format is not self-describing, so the next word
+    /// is provided as a string.
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let index = self.index;
+        let s = self.parse_str()?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(Error::Body(BodyError::InvalidWord {
+                index,
+                expected: "char",
+            })),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.parse_str()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_bytes(self.next_word()?.as_ref())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    /// Absent or empty words are `None`.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.words.get(self.index) {
+            None => visitor.visit_none(),
+            Some(word) if word.byte_size() == 0 => {
+                self.index += 1;
+                visitor.visit_none()
+            }
+            Some(_) => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    /// Strided sequences are prefixed with their length and stride.
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if name == STRIDED {
+            let len = self.parse::<usize>()?;
+            let stride = self.parse::<usize>()?;
+            return visitor.visit_seq(StridedAccess {
+                de: self,
+                remaining: len,
+                stride,
+            });
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Sequences are prefixed with their length.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.parse::<usize>()?;
+        visitor.visit_seq(Counted::new(self, len))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Counted::new(self, len))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    /// Maps are prefixed with their number of entries.
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.parse::<usize>()?;
+        visitor.visit_map(Counted::new(self, len))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.next_word()?;
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        true
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Access to a known number of elements or entries.
+struct Counted<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    remaining: usize,
+}
+
+impl<'a, 'de> Counted<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>, remaining: usize) -> Self {
+        Self { de, remaining }
+    }
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for Counted<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for Counted<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// Access to a known number of elements spanning `stride` words each.
+struct StridedAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    remaining: usize,
+    stride: usize,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for StridedAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        // The element only sees its own words, keeping their indices.
+        let end = (self.de.index + self.stride).min(self.de.words.len());
+        let mut element = Deserializer {
+            words: &self.de.words[..end],
+            index: self.de.index,
+        };
+        let value = seed.deserialize(&mut element)?;
+        self.de.index = end;
+        Ok(Some(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let name = self.parse_str()?;
+        let name: StrDeserializer<Error> = name.into_deserializer();
+        let variant = seed.deserialize(name)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Counted::new(self, len))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(Counted::new(self, fields.len()))
+    }
+}
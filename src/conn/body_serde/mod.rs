@@ -0,0 +1,252 @@
+//! A serde data format over the words of a `Body`.
+//!
+//! The format is not self-describing. Values map to words as follows:
+//!
+//! - Booleans, integers, floats, chars and strings are a single word,
+//!   using the same representation as `FromWord` and `ToWord`.
+//! - `None` is an empty word, or the absence of any remaining words.
+//!   `Some` is the inner value.
+//! - Structs, tuples and tuple structs are their fields in order.
+//! - Sequences and maps are prefixed with a word holding their length,
+//!   followed by their elements or entries in order.
+//! - [`Strided`] sequences are prefixed with their length and the number
+//!   of words per element, as map lists are. Absent trailing words of an
+//!   element are `None`, and words an element does not read are skipped.
+//! - Enum variants are a word holding the variant name, followed by
+//!   the variant's fields, if any.
+
+mod de;
+mod ser;
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::{de as serde_de, ser as serde_ser};
+use serde::{Deserialize, Serialize};
+
+use super::BodyError;
+
+pub use self::de::{from_body, from_words, Deserializer};
+pub use self::ser::{to_body, to_words, Serializer};
+
+/// The name [`Strided`] is serialized with, telling the format apart.
+pub(crate) const STRIDED: &str = "$battlelayer::Strided";
+
+/// A sequence of elements spanning the same number of words, such as the
+/// entries of `mapList.list`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Strided<T>(pub Vec<T>);
+
+impl<T: Serialize> Serialize for Strided<T> {
+    fn serialize<S: serde_ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(STRIDED, &self.0)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Strided<T> {
+    fn deserialize<D: serde_de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(STRIDED, StridedVisitor(PhantomData))
+    }
+}
+
+struct StridedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> serde_de::Visitor<'de> for StridedVisitor<T> {
+    type Value = Strided<T>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "a strided sequence")
+    }
+
+    fn visit_seq<A: serde_de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut elements = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(Strided(elements))
+    }
+
+    /// Other formats see a plain sequence.
+    fn visit_newtype_struct<D: serde_de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        Vec::deserialize(deserializer).map(Strided)
+    }
+}
+
+/// Represents a failure while serializing or deserializing words.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// A custom error from a `Serialize` or `Deserialize` implementation.
+    Message(String),
+    /// A word was missing, invalid, or could not be created.
+    Body(BodyError),
+    /// Words remained after deserializing, starting at the index.
+    TrailingWords(usize),
+}
+
+impl From<BodyError> for Error {
+    fn from(err: BodyError) -> Self {
+        Error::Body(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Message(msg) => write!(fmt, "{}", msg),
+            Error::Body(err) => write!(fmt, "{}", err),
+            Error::TrailingWords(index) => write!(fmt, "trailing words from index {}", index),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl serde_de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl serde_ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::conn::{Body, Word};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Scores {
+        scores: Vec<f32>,
+        target: u32,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Info {
+        name: String,
+        players: u32,
+        scores: Scores,
+        ranked: bool,
+        region: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct MapEntry {
+        map: String,
+        mode: String,
+        rounds: Option<u32>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Timeout {
+        #[serde(rename = "perm")]
+        Permanent,
+        #[serde(rename = "rounds")]
+        Rounds(u32),
+    }
+
+    #[test]
+    fn struct_round_trip_test() {
+        let body = Body::new(vec!["Server", "12", "2", "300", "250.5", "0", "true"]).unwrap();
+        let info: Info = from_body(&body).unwrap();
+        assert_eq!(
+            info,
+            Info {
+                name: "Server".to_string(),
+                players: 12,
+                scores: Scores {
+                    scores: vec![300.0, 250.5],
+                    target: 0,
+                },
+                ranked: true,
+                region: None,
+            }
+        );
+        let mut words = body.to_vec();
+        words.push(Word::new("").unwrap());
+        assert_eq!(to_body(&info).unwrap().words(), &words[..]);
+    }
+
+    #[test]
+    fn map_list_round_trip_test() {
+        let entry = |map: &str, mode: &str, rounds| MapEntry {
+            map: map.to_string(),
+            mode: mode.to_string(),
+            rounds,
+        };
+        let body = Body::new(vec![
+            "2",
+            "3",
+            "MP_Prison",
+            "ConquestLarge0",
+            "2",
+            "MP_Abandoned",
+            "RushLarge0",
+            "1",
+        ])
+        .unwrap();
+        let list: Strided<MapEntry> = from_body(&body).unwrap();
+        assert_eq!(
+            list.0,
+            [
+                entry("MP_Prison", "ConquestLarge0", Some(2)),
+                entry("MP_Abandoned", "RushLarge0", Some(1)),
+            ]
+        );
+        assert_eq!(to_body(&list).unwrap(), body);
+
+        // Elements shorter or longer than the type.
+        let body = Body::new(vec![
+            "2",
+            "2",
+            "MP_Prison",
+            "RushLarge0",
+            "MP_Siege",
+            "SQDM",
+        ]);
+        let list: Strided<MapEntry> = from_body(&body.unwrap()).unwrap();
+        assert_eq!(list.0[1], entry("MP_Siege", "SQDM", None));
+        let body = Body::new(vec!["1", "4", "MP_Prison", "RushLarge0", "2", "extra"]);
+        let list: Strided<MapEntry> = from_body(&body.unwrap()).unwrap();
+        assert_eq!(list.0, [entry("MP_Prison", "RushLarge0", Some(2))]);
+
+        // `None` is an empty word, but sequences vary in length.
+        let uneven = Strided(vec![vec![1u32], vec![2, 3]]);
+        assert!(to_body(&uneven).is_err());
+    }
+
+    #[test]
+    fn enum_round_trip_test() {
+        let body = to_body(&Timeout::Rounds(3)).unwrap();
+        assert_eq!(body.words()[0].as_str(), "rounds");
+        assert_eq!(from_body::<Timeout>(&body).unwrap(), Timeout::Rounds(3));
+        let body = Body::new(vec!["perm"]).unwrap();
+        assert_eq!(from_body::<Timeout>(&body).unwrap(), Timeout::Permanent);
+    }
+
+    #[test]
+    fn errors_test() {
+        let body = Body::new(vec!["Server", "x"]).unwrap();
+        assert_eq!(
+            from_body::<(String, u32)>(&body),
+            Err(Error::Body(BodyError::InvalidWord {
+                index: 1,
+                expected: "u32"
+            }))
+        );
+        assert_eq!(
+            from_words::<(String,)>(body.words()),
+            Err(Error::TrailingWords(1))
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use std::convert::TryFrom;
+
+use serde::ser::{self, Serialize};
+
+use super::{Error, STRIDED};
+use crate::conn::{Body, ToWord, Word};
+
+/// Serializes a typed value into a body.
+pub fn to_body<T>(value: &T) -> Result<Body, Error>
+where
+    T: Serialize + ?Sized,
+{
+    to_words(value).map(Body::from)
+}
+
+/// Serializes a typed value into words.
+pub fn to_words<T>(value: &T) -> Result<Vec<Word>, Error>
+where
+    T: Serialize + ?Sized,
+{
+    let mut serializer = Serializer::new();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_words())
+}
+
+/// A serializer writing typed values as words.
+#[derive(Debug, Default)]
+pub struct Serializer {
+    words: Vec<Word>,
+    /// Whether the next sequence is strided
+    strided: bool,
+}
+
+impl Serializer {
+    /// Creates a serializer with no words written.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consumes the serializer, returning the words written.
+    pub fn into_words(self) -> Vec<Word> {
+        self.words
+    }
+
+    fn push<T: ToWord>(&mut self, value: T) -> Result<(), Error> {
+        self.words.push(value.to_word());
+        Ok(())
+    }
+
+    fn push_str(&mut self, value: &str) -> Result<(), Error> {
+        self.words.push(Word::new(value)?);
+        Ok(())
+    }
+}
+
+macro_rules! serialize_word {
+    ($($method:ident($ty:ty)),*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<(), Error> {
+                self.push(value)
+            }
+        )*
+    };
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Prefixed<'a>;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Prefixed<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    serialize_word! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64)
+    }
+
+    fn serialize_char(self, value: char) -> Result<(), Error> {
+        self.push_str(value.encode_utf8(&mut [0u8; 4]))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<(), Error> {
+        self.push_str(value)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), Error> {
+        self.words.push(Word::try_from(value)?);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.push_str("")
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.push_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.strided = name == STRIDED;
+        let res = value.serialize(&mut *self);
+        self.strided = false;
+        res
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.push_str(variant)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Prefixed<'a>, Error> {
+        let strided = std::mem::replace(&mut self.strided, false);
+        Ok(Prefixed::new(self, strided))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.push_str(variant)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Prefixed<'a>, Error> {
+        Ok(Prefixed::new(self, false))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.push_str(variant)?;
+        Ok(self)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Serializes a sequence or map, prefixed with its length once known,
+/// and for strided sequences with the number of words per element.
+pub struct Prefixed<'a> {
+    ser: &'a mut Serializer,
+    len_index: usize,
+    len: usize,
+    strided: bool,
+    stride: Option<usize>,
+}
+
+impl<'a> Prefixed<'a> {
+    fn new(ser: &'a mut Serializer, strided: bool) -> Self {
+        let len_index = ser.words.len();
+        // Placeholders, replaced with the length and stride on end.
+        ser.words.push(0usize.to_word());
+        if strided {
+            ser.words.push(0usize.to_word());
+        }
+        Self {
+            ser,
+            len_index,
+            len: 0,
+            strided,
+            stride: None,
+        }
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        self.ser.words[self.len_index] = self.len.to_word();
+        if self.strided {
+            self.ser.words[self.len_index + 1] = self.stride.unwrap_or(0).to_word();
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeSeq for Prefixed<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.len += 1;
+        let start = self.ser.words.len();
+        value.serialize(&mut *self.ser)?;
+        if self.strided {
+            let stride = self.ser.words.len() - start;
+            if *self.stride.get_or_insert(stride) != stride {
+                return Err(Error::Message(format!(
+                    "strided element {} spans {} words instead of {}",
+                    self.len - 1,
+                    stride,
+                    self.stride.unwrap_or_default()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeMap for Prefixed<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.len += 1;
+        key.serialize(&mut *self.ser)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+macro_rules! impl_serialize_fields {
+    ($($trait:ident),*) => {
+        $(
+            impl ser::$trait for &mut Serializer {
+                type Ok = ();
+                type Error = Error;
+
+                fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> Result<(), Error> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_serialize_fields!(SerializeTupleStruct, SerializeTupleVariant);
+
+impl ser::SerializeTuple for &mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_serialize_named_fields {
+    ($($trait:ident),*) => {
+        $(
+            impl ser::$trait for &mut Serializer {
+                type Ok = ();
+                type Error = Error;
+
+                fn serialize_field<T: Serialize + ?Sized>(
+                    &mut self,
+                    _key: &'static str,
+                    value: &T,
+                ) -> Result<(), Error> {
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> Result<(), Error> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_serialize_named_fields!(SerializeStruct, SerializeStructVariant);
@@ -0,0 +1,373 @@
+//! Packet capture files.
+//!
+//! A capture file starts with the 8 byte magic `BLCAP01\n`, followed by
+//! zero or more records until the end of the file. Each record is laid
+//! out as follows, with integers in little endian:
+//!
+//! | Size     | Content                                            |
+//! |----------|----------------------------------------------------|
+//! | 8 bytes  | Timestamp, in microseconds since the UNIX epoch    |
+//! | 1 byte   | Direction, `0` for inbound and `1` for outbound    |
+//! | variable | The packet in its wire representation              |
+//!
+//! The packet is self-delimiting, as its wire representation starts with
+//! the packet sequence followed by the total packet size.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, BufWriter, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::BytesMut;
+
+use super::packet::{read_packet, write_packet, PacketError, PACKET_HEADER_SIZE, PACKET_MAX_SIZE};
+use super::Packet;
+
+const CAPTURE_MAGIC: &[u8; 8] = b"BLCAP01\n";
+const RECORD_HEADER_SIZE: usize = 9;
+
+/// The direction a packet travelled, relative to the capturing end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    /// The packet was received.
+    Inbound,
+    /// The packet was sent.
+    Outbound,
+}
+
+impl Direction {
+    fn to_raw(self) -> u8 {
+        match self {
+            Direction::Inbound => 0,
+            Direction::Outbound => 1,
+        }
+    }
+
+    fn from_raw(raw: u8) -> Result<Self, CaptureError> {
+        match raw {
+            0 => Ok(Direction::Inbound),
+            1 => Ok(Direction::Outbound),
+            other => Err(CaptureError::InvalidDirection(other)),
+        }
+    }
+}
+
+/// A single captured packet.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub timestamp: SystemTime,
+    pub direction: Direction,
+    pub packet: Packet,
+}
+
+impl Record {
+    /// Creates a record of a packet, timestamped now.
+    pub fn now(direction: Direction, packet: Packet) -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            direction,
+            packet,
+        }
+    }
+}
+
+/// Represents a failure while reading or writing a capture.
+#[derive(Debug)]
+pub enum CaptureError {
+    Io(io::Error),
+    Packet(PacketError),
+    InvalidMagic,
+    InvalidDirection(u8),
+    Truncated,
+}
+
+impl From<io::Error> for CaptureError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<PacketError> for CaptureError {
+    fn from(err: PacketError) -> Self {
+        Self::Packet(err)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Writes records to a capture.
+pub struct CaptureWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Creates a capture writer, writing the capture magic.
+    pub fn new(mut inner: W) -> Result<Self, CaptureError> {
+        inner.write_all(&CAPTURE_MAGIC[..])?;
+        Ok(Self { inner })
+    }
+
+    /// Writes a record to the capture.
+    pub fn write_record(&mut self, record: &Record) -> Result<(), CaptureError> {
+        let timestamp = record
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let timestamp = u64::try_from(timestamp.as_micros()).unwrap_or(u64::MAX);
+        let mut buf = BytesMut::with_capacity(RECORD_HEADER_SIZE + record.packet.byte_size());
+        buf.extend_from_slice(&timestamp.to_le_bytes()[..]);
+        buf.extend_from_slice(&[record.direction.to_raw()]);
+        write_packet(&mut buf, record.packet.clone())?;
+        self.inner.write_all(&buf[..])?;
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), CaptureError> {
+        Ok(self.inner.flush()?)
+    }
+
+    /// Consumes the capture writer, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Reads records from a capture.
+pub struct CaptureReader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Creates a capture reader, validating the capture magic.
+    pub fn new(mut inner: R) -> Result<Self, CaptureError> {
+        let mut magic = [0u8; 8];
+        if !read_exact_or_eof(&mut inner, &mut magic[..])? || &magic != CAPTURE_MAGIC {
+            return Err(CaptureError::InvalidMagic);
+        }
+        Ok(Self { inner })
+    }
+
+    /// Reads the next record, returning `None` at the end of the capture.
+    pub fn read_record(&mut self) -> Result<Option<Record>, CaptureError> {
+        let mut header = [0u8; RECORD_HEADER_SIZE + PACKET_HEADER_SIZE];
+        if !read_exact_or_eof(&mut self.inner, &mut header[..])? {
+            return Ok(None);
+        }
+        let mut timestamp = [0u8; 8];
+        timestamp.copy_from_slice(&header[..8]);
+        let timestamp = UNIX_EPOCH + Duration::from_micros(u64::from_le_bytes(timestamp));
+        let direction = Direction::from_raw(header[8])?;
+        // Extract the packet size from the packet header.
+        let mut packet_size = [0u8; 4];
+        packet_size.copy_from_slice(&header[RECORD_HEADER_SIZE + 4..RECORD_HEADER_SIZE + 8]);
+        let packet_size = u32::from_le_bytes(packet_size) as usize;
+        if !(PACKET_HEADER_SIZE..=PACKET_MAX_SIZE).contains(&packet_size) {
+            return Err(PacketError::InvalidSize(packet_size).into());
+        }
+        // Read the rest of the packet.
+        let mut packet_buf = vec![0u8; packet_size];
+        packet_buf[..PACKET_HEADER_SIZE].copy_from_slice(&header[RECORD_HEADER_SIZE..]);
+        if !read_exact_or_eof(&mut self.inner, &mut packet_buf[PACKET_HEADER_SIZE..])? {
+            return Err(CaptureError::Truncated);
+        }
+        match read_packet(&mut BytesMut::from(&packet_buf[..]))? {
+            Some(packet) => Ok(Some(Record {
+                timestamp,
+                direction,
+                packet,
+            })),
+            None => Err(CaptureError::Truncated),
+        }
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = Result<Record, CaptureError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Fills the buffer, returning `false` if the reader was already at EOF.
+fn read_exact_or_eof<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<bool, CaptureError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(CaptureError::Truncated),
+            Ok(n) => filled += n,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(true)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Records packets passing through a `Socket` into a capture.
+///
+/// Records are buffered, and flushed once the buffer fills up or the
+/// recorder is dropped. When recording through a `Socket` or
+/// `Connection`, a failure stops the recording rather than the
+/// connection, and is reported through the recorder's status.
+pub struct Recorder {
+    writer: CaptureWriter<BufWriter<Box<dyn Write + Send>>>,
+    status: RecorderStatus,
+}
+
+impl Recorder {
+    /// Creates a recorder writing to the given writer.
+    pub fn new<W>(writer: W) -> Result<Self, CaptureError>
+    where
+        W: Write + Send + 'static,
+    {
+        let writer: Box<dyn Write + Send> = Box::new(writer);
+        Ok(Self {
+            writer: CaptureWriter::new(BufWriter::new(writer))?,
+            status: RecorderStatus::default(),
+        })
+    }
+
+    /// Returns a handle to the status of the recorder, which remains
+    /// usable once the recorder is handed to a connection.
+    pub fn status(&self) -> RecorderStatus {
+        self.status.clone()
+    }
+
+    /// Records a packet, timestamped now, returning whether recording
+    /// continues. On failure, recording stops and the error is kept in
+    /// the status of the recorder.
+    pub fn record(&mut self, direction: Direction, packet: &Packet) -> bool {
+        let mut inner = self.status.inner.lock().unwrap();
+        if inner.failed {
+            return false;
+        }
+        let record = Record::now(direction, packet.clone());
+        if let Err(err) = self.writer.write_record(&record) {
+            inner.failed = true;
+            inner.error = Some(err);
+        }
+        !inner.failed
+    }
+
+    /// Flushes the records buffered so far.
+    pub fn flush(&mut self) -> Result<(), CaptureError> {
+        self.writer.flush()
+    }
+}
+
+/// The status of a recorder, shared with the recorder.
+#[derive(Debug, Clone, Default)]
+pub struct RecorderStatus {
+    inner: Arc<Mutex<StatusInner>>,
+}
+
+#[derive(Debug, Default)]
+struct StatusInner {
+    failed: bool,
+    error: Option<CaptureError>,
+}
+
+impl RecorderStatus {
+    /// Returns whether recording stopped on a failure.
+    pub fn has_failed(&self) -> bool {
+        self.inner.lock().unwrap().failed
+    }
+
+    /// Takes the failure that stopped recording, if not already taken.
+    pub fn take_error(&self) -> Option<CaptureError> {
+        self.inner.lock().unwrap().error.take()
+    }
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Recorder").finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conn::{PacketKind, PacketSequence, Role, Word};
+
+    fn packet(kind: PacketKind, origin: Role, num: u32, words: &[&str]) -> Packet {
+        let seq = PacketSequence::new(kind, origin, num).unwrap();
+        let words = words.iter().map(|w| Word::new(w).unwrap()).collect();
+        Packet::new(seq, words)
+    }
+
+    #[test]
+    fn capture_round_trip_test() {
+        let records = [
+            Record::now(
+                Direction::Outbound,
+                packet(PacketKind::Request, Role::Client, 0, &["serverInfo"]),
+            ),
+            Record::now(
+                Direction::Inbound,
+                packet(PacketKind::Response, Role::Client, 0, &["OK", "", "a b"]),
+            ),
+        ];
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        for record in records.iter() {
+            writer.write_record(record).unwrap();
+        }
+        let bytes = writer.into_inner();
+
+        let reader = CaptureReader::new(&bytes[..]).unwrap();
+        let read: Vec<Record> = reader.map(Result::unwrap).collect();
+        assert_eq!(read.len(), records.len());
+        for (a, b) in read.iter().zip(records.iter()) {
+            assert_eq!(a.direction, b.direction);
+            assert_eq!(a.packet.seq, b.packet.seq);
+            assert_eq!(a.packet.words, b.packet.words);
+            assert_eq!(
+                a.timestamp.duration_since(UNIX_EPOCH).unwrap().as_micros(),
+                b.timestamp.duration_since(UNIX_EPOCH).unwrap().as_micros()
+            );
+        }
+    }
+
+    #[test]
+    fn capture_truncated_test() {
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        writer
+            .write_record(&Record::now(
+                Direction::Inbound,
+                packet(PacketKind::Request, Role::Server, 1, &["player.onJoin"]),
+            ))
+            .unwrap();
+        let bytes = writer.into_inner();
+        for &len in &[bytes.len() - 3, CAPTURE_MAGIC.len() + 5] {
+            let mut reader = CaptureReader::new(&bytes[..len]).unwrap();
+            match reader.read_record() {
+                Err(CaptureError::Truncated) => (),
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn capture_oversized_test() {
+        let mut bytes = CAPTURE_MAGIC.to_vec();
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        let mut reader = CaptureReader::new(&bytes[..]).unwrap();
+        match reader.read_record() {
+            Err(CaptureError::Packet(PacketError::InvalidSize(size))) => {
+                assert_eq!(size, u32::MAX as usize)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Shell-like command line words.
+//!
+//! Words are separated by whitespace. Double quotes group characters,
+//! including whitespace, into a word, and `""` forms an empty word.
+//! A backslash escapes the character following it, both inside and
+//! outside of quotes.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::{BodyError, Word};
+
+/// Represents a failure to parse a command line.
+#[derive(Debug, PartialEq)]
+pub enum CommandLineError {
+    /// A word is not valid in a body.
+    Body(BodyError),
+    /// The line ended within a quoted word.
+    UnclosedQuote,
+    /// The line ended with an escape character.
+    TrailingEscape,
+}
+
+impl From<BodyError> for CommandLineError {
+    fn from(err: BodyError) -> Self {
+        Self::Body(err)
+    }
+}
+
+impl fmt::Display for CommandLineError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandLineError::Body(err) => err.fmt(fmt),
+            CommandLineError::UnclosedQuote => write!(fmt, "unclosed quote"),
+            CommandLineError::TrailingEscape => write!(fmt, "trailing escape character"),
+        }
+    }
+}
+
+/// Splits a command line into words.
+pub fn parse(line: &str) -> Result<Vec<Word>, CommandLineError> {
+    let mut words = Vec::new();
+    let mut chars = line.chars();
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let escaped = chars.next().ok_or(CommandLineError::TrailingEscape)?;
+                current.get_or_insert_with(String::new).push(escaped);
+            }
+            '"' => {
+                quoted = !quoted;
+                current.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if let Some(word) = current.take() {
+                    words.push(Word::try_from(word)?);
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quoted {
+        return Err(CommandLineError::UnclosedQuote);
+    }
+    if let Some(word) = current.take() {
+        words.push(Word::try_from(word)?);
+    }
+    Ok(words)
+}
+
+/// Formats words as a command line, quoting words where required
+/// so the line parses back into the same words.
+pub fn format(words: &[Word]) -> String {
+    let mut line = String::new();
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            line.push(' ');
+        }
+        let word = word.as_str();
+        let needs_quotes = word.is_empty()
+            || word
+                .chars()
+                .any(|c| c.is_whitespace() || c == '"' || c == '\\');
+        if !needs_quotes {
+            line.push_str(word);
+            continue;
+        }
+        line.push('"');
+        for c in word.chars() {
+            if c == '"' || c == '\\' {
+                line.push('\\');
+            }
+            line.push(c);
+        }
+        line.push('"');
+    }
+    line
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<Word> {
+        words.iter().map(|w| Word::new(w).unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(
+            parse(r#"admin.say "hello world" all"#).unwrap(),
+            words(&["admin.say", "hello world", "all"])
+        );
+        assert_eq!(
+            parse(r#"  vars.gamePassword ""  "#).unwrap(),
+            words(&["vars.gamePassword", ""])
+        );
+        assert_eq!(
+            parse(r#"a\ b "say \"hi\"" c\\"#).unwrap(),
+            words(&["a b", r#"say "hi""#, r"c\"])
+        );
+        assert_eq!(parse(r#"a "b"#), Err(CommandLineError::UnclosedQuote));
+        assert_eq!(parse(r"a \"), Err(CommandLineError::TrailingEscape));
+        assert_eq!(
+            parse("a \"\u{e9}\""),
+            Err(CommandLineError::Body(BodyError::InvalidWordChar(0xc3)))
+        );
+    }
+
+    #[test]
+    fn format_round_trip_test() {
+        let original = words(&["admin.yell", "", "a \"quoted\" \\ word", "all"]);
+        let line = format(&original);
+        assert_eq!(line, r#"admin.yell "" "a \"quoted\" \\ word" all"#);
+        assert_eq!(parse(&line).unwrap(), original);
+    }
+}
@@ -11,9 +11,14 @@ use tokio_io::{AsyncRead, AsyncWrite};
 use tokio_net::{tcp::TcpStream, ToSocketAddrs};
 use tower_service::Service;
 
+use super::capture::{Direction, Recorder};
+use super::interceptor::{InterceptorChain, Verdict};
+use super::protocol::Event;
+use super::socket::Observer;
+use super::trace::Tracer;
 use super::{
-    respondable, Body, BodyError, Error, Handler, Packet, PacketKind, PacketSequence, Request,
-    Respondable, Response, Role, Socket, SocketError,
+    respondable, Body, BodyError, Error, Handler, Interceptor, Packet, PacketSequence, Protocol,
+    Request, Respondable, Response, Role, Socket, SocketError,
 };
 
 pub struct Connection {
@@ -27,7 +32,11 @@ impl Connection {
     where
         B: TryInto<Body, Error = BodyError>,
     {
-        let body = words.try_into()?;
+        self.send_body(words.try_into()?).await
+    }
+
+    /// Send a request with an already built body.
+    pub async fn send_body(&mut self, body: Body) -> Result<Body, Error> {
         let request = Request { body };
         let response = self.send_request(request).await?;
         Ok(response.body)
@@ -59,9 +68,12 @@ impl Service<Request> for Connection {
 
 ///////////////////////////////////////////////////////////////////////////////
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ConnectionBuilder {
     handler: Handler,
+    tracer: Option<Tracer>,
+    recorder: Option<Recorder>,
+    interceptors: InterceptorChain,
 }
 
 impl ConnectionBuilder {
@@ -74,6 +86,29 @@ impl ConnectionBuilder {
         self
     }
 
+    /// Traces every packet sent and received with the tracer, as left
+    /// by the interceptors.
+    pub fn tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// Records every packet sent and received with the recorder, as left
+    /// by the interceptors.
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Adds an interceptor to the connection's chain of interceptors.
+    pub fn interceptor<I>(mut self, interceptor: I) -> Self
+    where
+        I: Interceptor + 'static,
+    {
+        self.interceptors.push(interceptor);
+        self
+    }
+
     pub fn with_transport_and_exec<T, E>(
         self,
         transport: T,
@@ -84,7 +119,10 @@ impl ConnectionBuilder {
         E: Executor,
         T: Send + AsyncRead + AsyncWrite + Unpin + 'static,
     {
-        ConnectionProcess::new(transport, self.handler, role).start(exec)
+        let mut process = ConnectionProcess::new(transport, self.handler, self.interceptors, role);
+        process.observer.tracer = self.tracer;
+        process.observer.recorder = self.recorder;
+        process.start(exec)
     }
 
     pub fn with_transport<T>(self, transport: T, role: Role) -> Result<Connection, Error>
@@ -99,14 +137,6 @@ impl ConnectionBuilder {
     }
 }
 
-impl Default for ConnectionBuilder {
-    fn default() -> Self {
-        Self {
-            handler: Default::default(),
-        }
-    }
-}
-
 ///////////////////////////////////////////////////////////////////////////////
 
 type PendingResponseResult = Result<(PacketSequence, Response), Error>;
@@ -115,10 +145,11 @@ struct ConnectionProcess<T>
 where
     T: AsyncRead + AsyncWrite,
 {
-    next_seq: u32,
-    role: Role,
     sock: Socket<T>,
+    protocol: Protocol,
     handler: Handler,
+    interceptors: InterceptorChain,
+    observer: Observer,
     request_tx: Option<respondable::Sender>,
     request_rx: respondable::Receiver,
     pending_requests: HashMap<u32, respondable::Responder>,
@@ -129,15 +160,16 @@ impl<T> ConnectionProcess<T>
 where
     T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
-    pub fn new(transport: T, handler: Handler, role: Role) -> Self {
+    pub fn new(transport: T, handler: Handler, interceptors: InterceptorChain, role: Role) -> Self {
         let (request_tx, request_rx) = respondable::channel();
         Self {
-            role,
             handler,
+            interceptors,
+            observer: Observer::default(),
             request_rx,
-            next_seq: 0,
             request_tx: Some(request_tx),
             sock: Socket::new(transport),
+            protocol: Protocol::new(role),
             pending_requests: HashMap::new(),
             pending_responses: FuturesUnordered::new(),
         }
@@ -161,40 +193,31 @@ where
         }
     }
 
-    async fn handle_incoming_packet(&mut self, packet: Packet) -> Result<(), Error> {
-        if packet.seq.kind() == PacketKind::Request {
-            let packet_seq = packet.seq;
-            let packet_words = packet.words;
-            // TODO: Are BF4 servers compliant with their own standard?
-            // if packet.seq.origin() == self.role {
-            //     return Err(Error::OriginMismatch);
-            // }
-            // Build the request for the handler.
-            let request = Request {
-                body: packet_words.into(),
-            };
-            // Get the response built by handler.
-            let response_fut = self.handler.handle(request);
-            let response_fut = async move { Ok((packet_seq, response_fut.await?)) };
-            let boxed_response_fut = Box::pin(response_fut);
-            // Push to the queue.
-            self.pending_responses.push(boxed_response_fut);
-            Ok(())
-        } else {
-            if packet.seq.origin() != self.role {
-                return Err(Error::OriginMismatch);
+    fn handle_incoming_packet(&mut self, mut packet: Packet) -> Result<(), Error> {
+        if self.interceptors.inbound(&mut packet) == Verdict::Drop {
+            self.cancel_dropped(&packet);
+            return Ok(());
+        }
+        self.observer.observe(Direction::Inbound, &packet);
+        self.protocol.receive_packet(packet)?;
+        while let Some(event) = self.protocol.poll_event() {
+            match event {
+                Event::Request { seq, body } => {
+                    // Get the response built by handler.
+                    let response_fut = self.handler.handle(Request { body });
+                    let response_fut = async move { Ok((seq, response_fut.await?)) };
+                    // Push to the queue.
+                    self.pending_responses.push(Box::pin(response_fut));
+                }
+                Event::Response { seq, body } => {
+                    if let Some(responder) = self.pending_requests.remove(&seq) {
+                        // Ignore errors here.
+                        let _ = responder.send(Response { body });
+                    }
+                }
             }
-            let responder = self
-                .pending_requests
-                .remove(&packet.seq.number())
-                .ok_or(Error::InvalidSequence)?;
-            let response = Response {
-                body: packet.words.into(),
-            };
-            // Ignore errors here.
-            let _ = responder.send(response);
-            Ok(())
         }
+        Ok(())
     }
 
     async fn handle_outgoing_request(
@@ -202,18 +225,12 @@ where
         outbound_request: Respondable,
     ) -> Result<(), Error> {
         let (request, reponder) = outbound_request.split();
-        // Get next sequence number
-        let seq_num = self.next_seq;
-        self.next_seq += 1;
-        // Build the packet
-        let seq = PacketSequence::new(PacketKind::Request, self.role, seq_num)
-            .map_err(|_| Error::InvalidSequence)?;
-        let packet = Packet::new(seq, request.body.to_vec());
-        // Send it braz
-        self.sock.send(packet).await?;
+        // Queue the request packet.
+        let seq_num = self.protocol.send_request(request.body)?;
         // Add the responder to the queue
         self.pending_requests.insert(seq_num, reponder);
-        Ok(())
+        // Send it braz
+        self.flush_transmit().await
     }
 
     async fn handle_outgoing_response(
@@ -221,16 +238,33 @@ where
         outbound_response: (PacketSequence, Response),
     ) -> Result<(), Error> {
         let (request_seq, response) = outbound_response;
-        // Build the response packet.
-        let response_seq = PacketSequence::new(
-            PacketKind::Response,
-            request_seq.origin(),
-            request_seq.number(),
-        )
-        .map_err(|_| Error::InvalidSequence)?;
-        let response_packet = Packet::new(response_seq, response.body.to_vec());
+        // Queue the response packet.
+        self.protocol.respond(request_seq, response.body)?;
         // Send it braz
-        Ok(self.sock.send(response_packet).await?)
+        self.flush_transmit().await
+    }
+
+    async fn flush_transmit(&mut self) -> Result<(), Error> {
+        while let Some(mut packet) = self.protocol.poll_transmit() {
+            if self.interceptors.outbound(&mut packet) == Verdict::Drop {
+                self.cancel_dropped(&packet);
+                continue;
+            }
+            self.observer.observe(Direction::Outbound, &packet);
+            self.sock.send(packet).await?;
+        }
+        Ok(())
+    }
+
+    /// Cancels a request of ours whose request or response was dropped,
+    /// as it will never be answered. Dropping its responder resolves the
+    /// request with `Error::RequestCancelled`.
+    fn cancel_dropped(&mut self, packet: &Packet) {
+        let seq = packet.seq;
+        if seq.origin() == self.protocol.role() {
+            self.protocol.cancel_request(seq.number());
+            self.pending_requests.remove(&seq.number());
+        }
     }
 
     async fn run(&mut self) -> Result<(), Error> {
@@ -238,7 +272,7 @@ where
             select! {
                 sock_res = self.sock.next() => {
                     let packet = sock_res.unwrap_or(Err(SocketError::Closed))?;
-                    self.handle_incoming_packet(packet).await?;
+                    self.handle_incoming_packet(packet)?;
                 },
                 outbound_request_opt = self.request_rx.next() => {
                     let outbound_request = outbound_request_opt.ok_or(SocketError::Closed)?;
@@ -253,3 +287,191 @@ where
         }
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::conn::capture::CaptureReader;
+    use crate::conn::testing::{FakeServer, TestExecutor};
+    use crate::conn::{PacketKind, Word};
+
+    struct DropCommand(&'static str);
+
+    impl Interceptor for DropCommand {
+        fn on_outbound(&mut self, packet: &mut Packet) -> Verdict {
+            if packet.words[0].as_str() == self.0 {
+                Verdict::Drop
+            } else {
+                Verdict::Pass
+            }
+        }
+    }
+
+    #[test]
+    fn dropped_request_test() {
+        let exec = TestExecutor::new();
+        let server = FakeServer::new(|_| Some(Body::new(vec!["OK"]).unwrap()));
+        let requests = server.requests();
+        let mut conn = ConnectionBuilder::new()
+            .interceptor(DropCommand("admin.shutDown"))
+            .with_transport_and_exec(server, Role::Client, exec.clone())
+            .unwrap();
+
+        match exec.block_on(conn.send(vec!["admin.shutDown"])) {
+            Err(Error::RequestCancelled) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let body = exec.block_on(conn.send(vec!["version"])).unwrap();
+        assert_eq!(body.words()[0].as_str(), "OK");
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].words()[0].as_str(), "version");
+    }
+
+    struct DropResponse(&'static str);
+
+    impl Interceptor for DropResponse {
+        fn on_inbound(&mut self, packet: &mut Packet) -> Verdict {
+            if packet.seq.kind() == PacketKind::Response && packet.words[0].as_str() == self.0 {
+                Verdict::Drop
+            } else {
+                Verdict::Pass
+            }
+        }
+    }
+
+    #[test]
+    fn dropped_response_test() {
+        let exec = TestExecutor::new();
+        let server = FakeServer::new(|body| {
+            let status = match body.words()[0].as_str() {
+                "serverInfo" => "Lost",
+                _ => "OK",
+            };
+            Some(Body::new(vec![status]).unwrap())
+        });
+        let mut conn = ConnectionBuilder::new()
+            .interceptor(DropResponse("Lost"))
+            .with_transport_and_exec(server, Role::Client, exec.clone())
+            .unwrap();
+
+        match exec.block_on(conn.send(vec!["serverInfo"])) {
+            Err(Error::RequestCancelled) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let body = exec.block_on(conn.send(vec!["version"])).unwrap();
+        assert_eq!(body.words()[0].as_str(), "OK");
+    }
+
+    struct Mask(&'static str);
+
+    impl Mask {
+        fn mask(&self, packet: &mut Packet) -> Verdict {
+            for word in packet.words.iter_mut() {
+                if word.as_str() == self.0 {
+                    *word = Word::new("***").unwrap();
+                }
+            }
+            Verdict::Pass
+        }
+    }
+
+    impl Interceptor for Mask {
+        fn on_inbound(&mut self, packet: &mut Packet) -> Verdict {
+            self.mask(packet)
+        }
+
+        fn on_outbound(&mut self, packet: &mut Packet) -> Verdict {
+            self.mask(packet)
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn observe_intercepted_test() {
+        let exec = TestExecutor::new();
+        let server = FakeServer::new(|body| Body::new(vec!["OK", body.words()[1].as_str()]).ok());
+        let lines = Arc::new(Mutex::new(Vec::<String>::new()));
+        let tracer = {
+            let lines = lines.clone();
+            Tracer::new(move |line| lines.lock().unwrap().push(line.to_string()))
+        };
+        let capture = SharedBuf::default();
+        let mut conn = ConnectionBuilder::new()
+            .interceptor(Mask("hunter2"))
+            .tracer(tracer)
+            .recorder(Recorder::new(capture.clone()).unwrap())
+            .with_transport_and_exec(server, Role::Client, exec.clone())
+            .unwrap();
+
+        let body = exec.block_on(conn.send(vec!["login.plainText", "hunter2"]));
+        assert_eq!(body.unwrap().words()[1].as_str(), "***");
+        // Dropping the connection process flushes the recorder.
+        drop(conn);
+        drop(exec);
+
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| !line.contains("hunter2")));
+        let capture = capture.0.lock().unwrap();
+        let records: Vec<_> = CaptureReader::new(&capture[..])
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(records.len(), 2);
+        for record in records {
+            assert_eq!(record.packet.words[1].as_str(), "***");
+        }
+    }
+
+    struct FullDisk;
+
+    impl Write for FullDisk {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::WriteZero.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_recorder_test() {
+        let exec = TestExecutor::new();
+        let server = FakeServer::new(|_| Body::new(vec!["OK"]).ok());
+        let recorder = Recorder::new(FullDisk).unwrap();
+        let status = recorder.status();
+        let mut conn = ConnectionBuilder::new()
+            .recorder(recorder)
+            .with_transport_and_exec(server, Role::Client, exec.clone())
+            .unwrap();
+
+        // Large enough to overflow the recorder's buffer.
+        let word = "a".repeat(10 * 1024);
+        let body = exec.block_on(conn.send(vec!["admin.say", word.as_str(), "all"]));
+        assert_eq!(body.unwrap().words()[0].as_str(), "OK");
+        assert!(status.has_failed());
+        let body = exec.block_on(conn.send(vec!["version"]));
+        assert_eq!(body.unwrap().words()[0].as_str(), "OK");
+        assert!(status.take_error().is_some());
+        assert!(status.has_failed());
+    }
+}
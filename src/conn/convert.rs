@@ -0,0 +1,240 @@
+use std::convert::TryFrom;
+use std::net::IpAddr;
+
+use super::{BodyError, Word};
+
+/// Conversion of a word into a typed value.
+pub trait FromWord: Sized {
+    /// The name of the expected type, reported in errors.
+    const EXPECTED: &'static str;
+
+    /// Converts a word, returning `None` if it is not a valid
+    /// representation of the type.
+    fn from_word(word: &Word) -> Option<Self>;
+}
+
+/// Conversion of a typed value into a word.
+pub trait ToWord {
+    /// Converts the value into its word representation.
+    fn to_word(&self) -> Word;
+}
+
+/// Conversion of a run of words into a typed value.
+pub trait FromWords: Sized {
+    /// Reads the value from the words, advancing the reader past them.
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError>;
+}
+
+/// Conversion of a typed value into a run of words.
+pub trait ToWords {
+    /// Appends the words representing the value.
+    fn to_words(&self, words: &mut Vec<Word>);
+}
+
+/// A cursor reading typed values from words.
+///
+/// Errors report the index of the offending word, relative to the
+/// start of the words the reader was created with.
+#[derive(Debug, Clone)]
+pub struct WordReader<'a> {
+    words: &'a [Word],
+    index: usize,
+}
+
+impl<'a> WordReader<'a> {
+    /// Creates a reader starting at the first word.
+    pub fn new(words: &'a [Word]) -> Self {
+        Self { words, index: 0 }
+    }
+
+    /// Returns the index of the next word to be read.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the words yet to be read.
+    pub fn remaining(&self) -> &'a [Word] {
+        &self.words[self.index..]
+    }
+
+    /// Returns `true` if all words have been read.
+    pub fn is_empty(&self) -> bool {
+        self.index >= self.words.len()
+    }
+
+    /// Returns the next word without advancing the reader.
+    pub fn peek(&self) -> Option<&'a Word> {
+        self.words.get(self.index)
+    }
+
+    /// Reads the next word.
+    pub fn next_word(&mut self) -> Result<&'a Word, BodyError> {
+        let word = self
+            .words
+            .get(self.index)
+            .ok_or(BodyError::MissingWord(self.index))?;
+        self.index += 1;
+        Ok(word)
+    }
+
+    /// Reads the next word as a typed value.
+    pub fn parse<T: FromWord>(&mut self) -> Result<T, BodyError> {
+        let index = self.index;
+        T::from_word(self.next_word()?).ok_or(BodyError::InvalidWord {
+            index,
+            expected: T::EXPECTED,
+        })
+    }
+
+    /// Reads a typed value spanning one or more words.
+    pub fn decode<T: FromWords>(&mut self) -> Result<T, BodyError> {
+        T::from_words(self)
+    }
+
+    /// Builds an error for an invalid word at the index.
+    pub fn invalid(&self, index: usize, expected: &'static str) -> BodyError {
+        BodyError::InvalidWord { index, expected }
+    }
+}
+
+/// Creates a word from a string known to contain only valid characters.
+pub(crate) fn word_from_string(s: String) -> Word {
+    Word::try_from(s).expect("word built from valid characters")
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+impl FromWord for Word {
+    const EXPECTED: &'static str = "Word";
+
+    fn from_word(word: &Word) -> Option<Self> {
+        Some(word.clone())
+    }
+}
+
+impl ToWord for Word {
+    fn to_word(&self) -> Word {
+        self.clone()
+    }
+}
+
+impl FromWord for String {
+    const EXPECTED: &'static str = "String";
+
+    fn from_word(word: &Word) -> Option<Self> {
+        Some(word.as_str().to_string())
+    }
+}
+
+impl FromWord for bool {
+    const EXPECTED: &'static str = "bool";
+
+    fn from_word(word: &Word) -> Option<Self> {
+        let word = word.as_str();
+        if word.eq_ignore_ascii_case("true") {
+            Some(true)
+        } else if word.eq_ignore_ascii_case("false") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl ToWord for bool {
+    fn to_word(&self) -> Word {
+        Word::new(if *self { "true" } else { "false" }).unwrap()
+    }
+}
+
+/// Empty words represent the absence of a value.
+impl<T: FromWord> FromWord for Option<T> {
+    const EXPECTED: &'static str = T::EXPECTED;
+
+    fn from_word(word: &Word) -> Option<Self> {
+        if word.byte_size() == 0 {
+            Some(None)
+        } else {
+            T::from_word(word).map(Some)
+        }
+    }
+}
+
+impl<T: ToWord> ToWord for Option<T> {
+    fn to_word(&self) -> Word {
+        match self {
+            Some(value) => value.to_word(),
+            None => Word::new("").unwrap(),
+        }
+    }
+}
+
+macro_rules! impl_word_from_str {
+    ($($ty:ty),*) => {
+        $(
+            impl FromWord for $ty {
+                const EXPECTED: &'static str = stringify!($ty);
+
+                fn from_word(word: &Word) -> Option<Self> {
+                    word.as_str().parse().ok()
+                }
+            }
+
+            impl ToWord for $ty {
+                fn to_word(&self) -> Word {
+                    word_from_string(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_word_from_str!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64, IpAddr);
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conn::{Body, BodyError};
+
+    fn word(s: &str) -> Word {
+        Word::new(s).unwrap()
+    }
+
+    #[test]
+    fn word_primitives_test() {
+        assert_eq!(u32::from_word(&word("600")), Some(600));
+        assert_eq!(u32::from_word(&word("-1")), None);
+        assert_eq!(i32::from_word(&word("-1")), Some(-1));
+        assert_eq!(f32::from_word(&word("1.5")), Some(1.5));
+        assert_eq!(bool::from_word(&word("true")), Some(true));
+        assert_eq!(bool::from_word(&word("False")), Some(false));
+        assert_eq!(bool::from_word(&word("1")), None);
+        assert_eq!(true.to_word(), word("true"));
+        assert_eq!(25u32.to_word(), word("25"));
+        assert_eq!(0.5f64.to_word(), word("0.5"));
+    }
+
+    #[test]
+    fn word_option_test() {
+        assert_eq!(Option::<u32>::from_word(&word("")), Some(None));
+        assert_eq!(Option::<u32>::from_word(&word("3")), Some(Some(3)));
+        assert_eq!(Option::<u32>::from_word(&word("x")), None);
+        assert_eq!(None::<u32>.to_word(), word(""));
+    }
+
+    #[test]
+    fn body_get_test() {
+        let body = Body::new(vec!["OK", "64", "x"]).unwrap();
+        assert_eq!(body.get::<u32>(1), Ok(64));
+        assert_eq!(
+            body.get::<u32>(2),
+            Err(BodyError::InvalidWord {
+                index: 2,
+                expected: "u32"
+            })
+        );
+        assert_eq!(body.get::<u32>(3), Err(BodyError::MissingWord(3)));
+    }
+}
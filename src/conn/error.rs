@@ -1,21 +1,25 @@
 use std::io;
 
-use super::{BodyError, SocketError};
+use super::packet::PacketError;
+use super::BodyError;
+#[cfg(feature = "async")]
 use futures_channel::mpsc;
+#[cfg(feature = "async")]
 use tokio_executor::SpawnError;
 
 #[derive(Debug)]
 pub enum Error {
     Body(BodyError),
+    #[cfg(feature = "async")]
     Spawn(SpawnError),
     Socket(SocketError),
+    #[cfg(feature = "async")]
     Responder(mpsc::SendError),
     InvalidSequence,
     OriginMismatch,
     RequestFailed,
     RequestCancelled,
 }
-
 impl From<SocketError> for Error {
     fn from(err: SocketError) -> Self {
         Self::Socket(err)
@@ -33,3 +37,31 @@ impl From<BodyError> for Error {
         Error::Body(err)
     }
 }
+
+impl From<PacketError> for Error {
+    fn from(err: PacketError) -> Self {
+        SocketError::Packet(err).into()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug)]
+pub enum SocketError {
+    Broken,
+    Closed,
+    Io(io::Error),
+    Packet(PacketError),
+}
+
+impl From<io::Error> for SocketError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<PacketError> for SocketError {
+    fn from(err: PacketError) -> Self {
+        Self::Packet(err)
+    }
+}
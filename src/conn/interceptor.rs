@@ -0,0 +1,166 @@
+use std::fmt;
+
+use super::Packet;
+
+/// The outcome of intercepting a packet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    /// The packet continues on, possibly modified.
+    Pass,
+    /// The packet is discarded.
+    Drop,
+}
+
+/// Observes, modifies or drops packets passing through a connection.
+///
+/// Inbound packets are intercepted after they are read from the socket,
+/// and outbound packets before they are written to it. In both
+/// directions, tracers and recorders see packets as left by the
+/// interceptors, and do not see dropped packets. A request of ours
+/// whose request or response is dropped resolves with
+/// `Error::RequestCancelled`, whereas a dropped inbound request is never
+/// answered.
+pub trait Interceptor: Send {
+    /// Intercepts a packet received from the remote end.
+    fn on_inbound(&mut self, _packet: &mut Packet) -> Verdict {
+        Verdict::Pass
+    }
+
+    /// Intercepts a packet about to be sent to the remote end.
+    fn on_outbound(&mut self, _packet: &mut Packet) -> Verdict {
+        Verdict::Pass
+    }
+}
+
+/// An ordered chain of interceptors.
+///
+/// Inbound packets pass through the interceptors in the order they were
+/// added, and outbound packets in the reverse order.
+#[derive(Default)]
+pub(crate) struct InterceptorChain {
+    interceptors: Vec<Box<dyn Interceptor>>,
+}
+
+impl InterceptorChain {
+    pub fn push<I>(&mut self, interceptor: I)
+    where
+        I: Interceptor + 'static,
+    {
+        self.interceptors.push(Box::new(interceptor));
+    }
+
+    pub fn inbound(&mut self, packet: &mut Packet) -> Verdict {
+        for interceptor in self.interceptors.iter_mut() {
+            if interceptor.on_inbound(packet) == Verdict::Drop {
+                return Verdict::Drop;
+            }
+        }
+        Verdict::Pass
+    }
+
+    pub fn outbound(&mut self, packet: &mut Packet) -> Verdict {
+        for interceptor in self.interceptors.iter_mut().rev() {
+            if interceptor.on_outbound(packet) == Verdict::Drop {
+                return Verdict::Drop;
+            }
+        }
+        Verdict::Pass
+    }
+}
+
+impl fmt::Debug for InterceptorChain {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("InterceptorChain")
+            .field("len", &self.interceptors.len())
+            .finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::conn::{PacketKind, PacketSequence, Role, Word};
+
+    /// Appends its name to the packet, and drops packets carrying it.
+    struct Tag {
+        name: &'static str,
+        drop: &'static str,
+        seen: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Tag {
+        fn intercept(&mut self, packet: &mut Packet) -> Verdict {
+            self.seen.lock().unwrap().push(self.name);
+            if packet.words.iter().any(|word| word.as_str() == self.drop) {
+                return Verdict::Drop;
+            }
+            packet.words.push(Word::new(self.name).unwrap());
+            Verdict::Pass
+        }
+    }
+
+    impl Interceptor for Tag {
+        fn on_inbound(&mut self, packet: &mut Packet) -> Verdict {
+            self.intercept(packet)
+        }
+
+        fn on_outbound(&mut self, packet: &mut Packet) -> Verdict {
+            self.intercept(packet)
+        }
+    }
+
+    fn chain(seen: &Arc<Mutex<Vec<&'static str>>>) -> InterceptorChain {
+        let mut chain = InterceptorChain::default();
+        for (name, drop) in &[("a", "drop-a"), ("b", "drop-b")] {
+            chain.push(Tag {
+                name,
+                drop,
+                seen: seen.clone(),
+            });
+        }
+        chain
+    }
+
+    fn packet(word: &str) -> Packet {
+        let seq = PacketSequence::new(PacketKind::Request, Role::Client, 0).unwrap();
+        Packet::new(seq, vec![Word::new(word).unwrap()])
+    }
+
+    fn words(packet: &Packet) -> Vec<&str> {
+        packet.words.iter().map(|word| word.as_str()).collect()
+    }
+
+    #[test]
+    fn rewrite_test() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut chain = chain(&seen);
+
+        let mut inbound = packet("version");
+        assert_eq!(chain.inbound(&mut inbound), Verdict::Pass);
+        assert_eq!(words(&inbound), ["version", "a", "b"]);
+
+        let mut outbound = packet("version");
+        assert_eq!(chain.outbound(&mut outbound), Verdict::Pass);
+        assert_eq!(words(&outbound), ["version", "b", "a"]);
+        assert_eq!(*seen.lock().unwrap(), ["a", "b", "b", "a"]);
+    }
+
+    #[test]
+    fn drop_test() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut chain = chain(&seen);
+
+        // Interceptors after the dropping one are skipped.
+        assert_eq!(chain.inbound(&mut packet("drop-a")), Verdict::Drop);
+        assert_eq!(chain.outbound(&mut packet("drop-b")), Verdict::Drop);
+        assert_eq!(*seen.lock().unwrap(), ["a", "b"]);
+
+        assert_eq!(chain.inbound(&mut packet("drop-b")), Verdict::Drop);
+        assert_eq!(chain.outbound(&mut packet("drop-a")), Verdict::Drop);
+        assert_eq!(*seen.lock().unwrap(), ["a", "b", "a", "b", "b", "a"]);
+    }
+}
@@ -1,19 +1,50 @@
 mod body;
+mod command_line;
+#[cfg(feature = "async")]
 mod connection;
+mod convert;
 mod error;
+#[cfg(feature = "async")]
 mod handler;
+#[cfg(feature = "async")]
+mod interceptor;
+#[cfg(feature = "async")]
 mod socket;
+#[cfg(all(test, feature = "async"))]
+pub(crate) mod testing;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "serde")]
+pub mod body_serde;
+pub mod capture;
 pub mod packet;
+pub mod protocol;
+#[cfg(feature = "async")]
+pub mod replay;
+#[cfg(feature = "async")]
 pub mod respondable;
+pub mod trace;
 
 pub use self::body::{Body, BodyError, Word};
+#[cfg(feature = "serde")]
+pub use self::body_serde::{from_body, to_body};
+pub use self::command_line::CommandLineError;
+#[cfg(feature = "async")]
 pub use self::connection::{Connection, ConnectionBuilder};
-pub use self::error::Error;
+pub(crate) use self::convert::word_from_string;
+pub use self::convert::{FromWord, FromWords, ToWord, ToWords, WordReader};
+pub use self::error::{Error, SocketError};
+#[cfg(feature = "async")]
 pub use self::handler::{DefaultHandler, Handler, RespondableHandler};
+#[cfg(feature = "async")]
+pub use self::interceptor::{Interceptor, Verdict};
 pub use self::packet::{Packet, PacketKind, PacketSequence};
+pub use self::protocol::Protocol;
+#[cfg(feature = "async")]
 pub use self::respondable::Respondable;
-pub use self::socket::{Socket, SocketError};
+#[cfg(feature = "async")]
+pub use self::socket::Socket;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Role {
@@ -5,9 +5,9 @@ use bytes::{Buf, BufMut, BytesMut};
 
 use super::{BodyError, Role, Word};
 
-const PACKET_MAX_SIZE: usize = 16384;
-const PACKET_MAX_WORDS: usize = 256;
-const PACKET_HEADER_SIZE: usize = 12;
+pub(crate) const PACKET_MAX_SIZE: usize = 16384;
+pub(crate) const PACKET_MAX_WORDS: usize = 256;
+pub(crate) const PACKET_HEADER_SIZE: usize = 12;
 const PACKET_WORD_HEADER_FOOTER_SIZE: usize = 5;
 const PACKET_WORD_CONTENT_MIN_SIZE: usize = 0;
 const PACKET_WORD_CONTENT_MAX_SIZE: usize =
@@ -27,10 +27,9 @@ pub fn read_packet(buf: &mut BytesMut) -> Result<Option<Packet>, PacketError> {
     if buf.len() < PACKET_HEADER_SIZE {
         return Ok(None);
     }
-    // Split off the packet header to a seperate buf.
-    let header_buf = buf.split_to(PACKET_HEADER_SIZE);
-    // Create a cursor to read the header.
-    let mut header_cur = Cursor::new(header_buf.as_ref());
+    // Create a cursor to read the header, leaving it in the buf until
+    // the whole packet is there.
+    let mut header_cur = Cursor::new(&buf[..PACKET_HEADER_SIZE]);
     // Read the packet sequence.
     let seq = PacketSequence::from_raw(header_cur.get_u32_le());
     // Read the packet size.
@@ -41,12 +40,12 @@ pub fn read_packet(buf: &mut BytesMut) -> Result<Option<Packet>, PacketError> {
     let mut words = Vec::with_capacity(word_count);
     // Calculate the body size.
     let body_size = size - PACKET_HEADER_SIZE;
-    // Return early if we can't met the packet body size.
-    // We also rejoin the header buf, for another attempt.
-    if buf.len() < body_size {
-        buf.unsplit(header_buf);
+    // Return early if we can't met the packet size.
+    if buf.len() < size {
         return Ok(None);
     }
+    // Skip the header.
+    buf.advance(PACKET_HEADER_SIZE);
     // Read the body bytes.
     let mut body_buf = buf.split_to(body_size);
     // Read packet words.
@@ -84,6 +83,7 @@ pub fn read_packet(buf: &mut BytesMut) -> Result<Option<Packet>, PacketError> {
             Err(BodyError::InvalidWordChar(invalid_char)) => {
                 return Err(PacketError::InvalidWordChar(invalid_char))
             }
+            Err(_) => return Err(PacketError::Malformed),
         }
     }
     Ok(Some(Packet { seq, words }))
@@ -117,7 +117,7 @@ pub fn write_packet(buf: &mut BytesMut, packet: Packet) -> Result<(), PacketErro
 
 fn write_size_u32(buf: &mut BytesMut, size: usize) -> Result<(), PacketError> {
     // Validate the usize will fit inside a u32.
-    if size > (u32::max_value() as usize) {
+    if size > (u32::MAX as usize) {
         return Err(PacketError::InvalidSize(size));
     }
     // Write the size to the buf.
@@ -141,7 +141,7 @@ fn read_u32_as_bounded_usize(
 
 ////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Packet {
     pub seq: PacketSequence,
     pub words: Vec<Word>,
@@ -167,6 +167,24 @@ impl Packet {
     }
 }
 
+impl fmt::Display for Packet {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let origin = match self.seq.origin() {
+            Role::Client => "client",
+            Role::Server => "server",
+        };
+        let kind = match self.seq.kind() {
+            PacketKind::Request => "request",
+            PacketKind::Response => "response",
+        };
+        write!(fmt, "#{} {} {}", self.seq.number(), origin, kind)?;
+        for word in self.words.iter() {
+            write!(fmt, " {:?}", word.as_str())?;
+        }
+        Ok(())
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 /// Represents a failure while handling a packet.
@@ -178,7 +196,7 @@ pub enum PacketError {
     InvalidSequenceNumber,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PacketKind {
     /// Indicates the packet forms a request.
     Request,
@@ -189,6 +207,7 @@ pub enum PacketKind {
 ////////////////////////////////////////////////////////////////////////////////
 
 /// The sequence structure of a packet.
+#[derive(Clone, Copy, PartialEq)]
 pub struct PacketSequence {
     raw: u32,
 }
@@ -286,14 +305,22 @@ mod tests {
         assert_eq!(
             &packet.words[..],
             &[
-                PacketWord::new("hello").unwrap(),
-                PacketWord::new("world").unwrap(),
-                PacketWord::new("ok").unwrap(),
+                Word::new("hello").unwrap(),
+                Word::new("world").unwrap(),
+                Word::new("ok").unwrap(),
             ]
         );
         let mut out = BytesMut::with_capacity(packet_bytes.len());
         write_packet(&mut out, packet).unwrap();
         assert_eq!(&out[..], &packet_bytes[..]);
+
+        // Incomplete packets are left in the buf until complete.
+        let mut buf = BytesMut::from(&packet_bytes[..20]);
+        assert!(read_packet(&mut buf).unwrap().is_none());
+        assert_eq!(&buf[..], &packet_bytes[..20]);
+        buf.extend_from_slice(&packet_bytes[20..]);
+        assert_eq!(read_packet(&mut buf).unwrap().unwrap().words.len(), 3);
+        assert!(buf.is_empty());
     }
 
     #[test]
@@ -0,0 +1,241 @@
+use std::collections::{HashSet, VecDeque};
+
+use bytes::BytesMut;
+
+use super::packet::{read_packet, write_packet};
+use super::{Body, Error, Packet, PacketKind, PacketSequence, Role};
+
+/// An event produced by the protocol state machine.
+#[derive(Debug)]
+pub enum Event {
+    /// The remote end sent a request, which must be answered with
+    /// `Protocol::respond` using the provided sequence.
+    Request { seq: PacketSequence, body: Body },
+    /// The remote end responded to a request previously sent
+    /// with `Protocol::send_request`.
+    Response { seq: u32, body: Body },
+}
+
+/// A sans-IO implementation of the protocol.
+///
+/// The protocol handles sequence allocation, request/response matching
+/// and origin handling, but performs no IO itself. Packets or raw bytes
+/// are fed in, and events and packets to transmit are polled out.
+#[derive(Debug)]
+pub struct Protocol {
+    role: Role,
+    next_seq: u32,
+    read_buf: BytesMut,
+    pending_requests: HashSet<u32>,
+    events: VecDeque<Event>,
+    transmit: VecDeque<Packet>,
+}
+
+impl Protocol {
+    /// Creates a new protocol state machine for the given role.
+    pub fn new(role: Role) -> Self {
+        Self {
+            role,
+            next_seq: 0,
+            read_buf: BytesMut::new(),
+            pending_requests: HashSet::new(),
+            events: VecDeque::new(),
+            transmit: VecDeque::new(),
+        }
+    }
+
+    /// Returns the role this end of the protocol plays.
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Returns the number of requests still awaiting a response.
+    pub fn pending_requests(&self) -> usize {
+        self.pending_requests.len()
+    }
+
+    /// Queues a request for transmission, returning the sequence
+    /// number its response will be reported with.
+    pub fn send_request(&mut self, body: Body) -> Result<u32, Error> {
+        // Get next sequence number
+        let seq_num = self.next_seq;
+        // Build the packet
+        let seq = PacketSequence::new(PacketKind::Request, self.role, seq_num)
+            .map_err(|_| Error::InvalidSequence)?;
+        self.next_seq += 1;
+        self.pending_requests.insert(seq_num);
+        self.transmit.push_back(Packet::new(seq, body.to_vec()));
+        Ok(seq_num)
+    }
+
+    /// Stops awaiting the response to a request, such as one that was
+    /// never transmitted. Returns whether the request was pending.
+    pub fn cancel_request(&mut self, seq_num: u32) -> bool {
+        self.pending_requests.remove(&seq_num)
+    }
+
+    /// Queues a response to a request received from the remote end.
+    pub fn respond(&mut self, request_seq: PacketSequence, body: Body) -> Result<(), Error> {
+        // Build the response packet.
+        let response_seq = PacketSequence::new(
+            PacketKind::Response,
+            request_seq.origin(),
+            request_seq.number(),
+        )
+        .map_err(|_| Error::InvalidSequence)?;
+        self.transmit
+            .push_back(Packet::new(response_seq, body.to_vec()));
+        Ok(())
+    }
+
+    /// Feeds a packet received from the remote end.
+    pub fn receive_packet(&mut self, packet: Packet) -> Result<(), Error> {
+        if packet.seq.kind() == PacketKind::Request {
+            // TODO: Are BF4 servers compliant with their own standard?
+            // if packet.seq.origin() == self.role {
+            //     return Err(Error::OriginMismatch);
+            // }
+            self.events.push_back(Event::Request {
+                seq: packet.seq,
+                body: packet.words.into(),
+            });
+        } else {
+            if packet.seq.origin() != self.role {
+                return Err(Error::OriginMismatch);
+            }
+            let seq_num = packet.seq.number();
+            if !self.pending_requests.remove(&seq_num) {
+                return Err(Error::InvalidSequence);
+            }
+            self.events.push_back(Event::Response {
+                seq: seq_num,
+                body: packet.words.into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Feeds raw bytes received from the remote end.
+    ///
+    /// Incomplete packets are buffered until the remaining bytes arrive.
+    pub fn receive_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.read_buf.extend_from_slice(bytes);
+        while let Some(packet) = read_packet(&mut self.read_buf)? {
+            self.receive_packet(packet)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the next event, if any.
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    /// Returns the next packet to transmit, if any.
+    pub fn poll_transmit(&mut self) -> Option<Packet> {
+        self.transmit.pop_front()
+    }
+
+    /// Writes the wire representation of all packets awaiting
+    /// transmission into a BytesMut.
+    pub fn write_transmit(&mut self, buf: &mut BytesMut) -> Result<(), Error> {
+        while let Some(packet) = self.poll_transmit() {
+            write_packet(buf, packet)?;
+        }
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(from: &mut Protocol, to: &mut Protocol) {
+        let mut buf = BytesMut::new();
+        from.write_transmit(&mut buf).unwrap();
+        to.receive_bytes(&buf[..]).unwrap();
+    }
+
+    #[test]
+    fn request_response_test() {
+        let mut client = Protocol::new(Role::Client);
+        let mut server = Protocol::new(Role::Server);
+
+        let seq = client
+            .send_request(Body::new(vec!["serverInfo"]).unwrap())
+            .unwrap();
+        assert_eq!(client.pending_requests(), 1);
+        exchange(&mut client, &mut server);
+
+        let request_seq = match server.poll_event() {
+            Some(Event::Request { seq, body }) => {
+                assert_eq!(body.words()[0].as_str(), "serverInfo");
+                seq
+            }
+            other => panic!("unexpected event: {:?}", other),
+        };
+        assert_eq!(request_seq.origin(), Role::Client);
+        server
+            .respond(request_seq, Body::new(vec!["OK"]).unwrap())
+            .unwrap();
+        exchange(&mut server, &mut client);
+
+        match client.poll_event() {
+            Some(Event::Response {
+                seq: response_seq,
+                body,
+            }) => {
+                assert_eq!(response_seq, seq);
+                assert_eq!(body.words()[0].as_str(), "OK");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert_eq!(client.pending_requests(), 0);
+        assert!(client.poll_event().is_none());
+    }
+
+    #[test]
+    fn partial_bytes_test() {
+        let mut client = Protocol::new(Role::Client);
+        let mut server = Protocol::new(Role::Server);
+
+        client
+            .send_request(Body::new(vec!["version"]).unwrap())
+            .unwrap();
+        let mut buf = BytesMut::new();
+        client.write_transmit(&mut buf).unwrap();
+
+        let (head, tail) = buf.split_at(5);
+        server.receive_bytes(head).unwrap();
+        assert!(server.poll_event().is_none());
+        server.receive_bytes(tail).unwrap();
+        assert!(server.poll_event().is_some());
+    }
+
+    #[test]
+    fn cancel_request_test() {
+        let mut client = Protocol::new(Role::Client);
+        let seq = client
+            .send_request(Body::new(vec!["version"]).unwrap())
+            .unwrap();
+        assert!(client.cancel_request(seq));
+        assert!(!client.cancel_request(seq));
+        assert_eq!(client.pending_requests(), 0);
+        // A late response to the request is unexpected.
+        let seq = PacketSequence::new(PacketKind::Response, Role::Client, seq).unwrap();
+        assert!(client.receive_packet(Packet::new(seq, Vec::new())).is_err());
+    }
+
+    #[test]
+    fn unexpected_response_test() {
+        let mut client = Protocol::new(Role::Client);
+        let seq = PacketSequence::new(PacketKind::Response, Role::Client, 7).unwrap();
+        let res = client.receive_packet(Packet::new(seq, Vec::new()));
+        match res {
+            Err(Error::InvalidSequence) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::VecDeque;
+use std::io::{self, Read};
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use bytes::BytesMut;
+use tokio_io::{AsyncRead, AsyncWrite};
+
+use super::capture::{CaptureError, CaptureReader, Direction, Record};
+use super::packet::{read_packet, write_packet};
+use super::{Packet, PacketKind, PacketSequence};
+
+/// A transport replaying the inbound packets of a capture.
+///
+/// Inbound packets are read back in order as fast as they are consumed,
+/// ignoring their timestamps, after which the transport reports EOF. An
+/// inbound response is only released once the request it answers, with
+/// the same origin and sequence number, has been written to the transport,
+/// so that a connection replaying a capture sees its requests answered.
+/// Anything else written to the transport is discarded.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    pending: VecDeque<Packet>,
+    inbound: BytesMut,
+    outbound: BytesMut,
+    written: Vec<PacketSequence>,
+    waker: Option<Waker>,
+}
+
+impl ReplayTransport {
+    /// Creates a replay transport from the records of a capture.
+    pub fn new<R: Read>(reader: CaptureReader<R>) -> Result<Self, CaptureError> {
+        let mut records = Vec::new();
+        for record in reader {
+            records.push(record?);
+        }
+        Self::from_records(records)
+    }
+
+    /// Creates a replay transport from a set of records.
+    pub fn from_records<I>(records: I) -> Result<Self, CaptureError>
+    where
+        I: IntoIterator<Item = Record>,
+    {
+        let mut pending = VecDeque::new();
+        for record in records {
+            if record.direction == Direction::Inbound {
+                // Fail early on packets that cannot be replayed.
+                write_packet(&mut BytesMut::new(), record.packet.clone())?;
+                pending.push_back(record.packet);
+            }
+        }
+        Ok(Self {
+            pending,
+            inbound: BytesMut::new(),
+            outbound: BytesMut::new(),
+            written: Vec::new(),
+            waker: None,
+        })
+    }
+
+    /// Releases the pending packets up to the next response whose request
+    /// has not been written yet.
+    fn release(&mut self) {
+        while let Some(packet) = self.pending.front() {
+            if packet.seq.kind() == PacketKind::Response {
+                let seq = packet.seq;
+                match self
+                    .written
+                    .iter()
+                    .position(|s| s.origin() == seq.origin() && s.number() == seq.number())
+                {
+                    Some(index) => {
+                        self.written.swap_remove(index);
+                    }
+                    None => return,
+                }
+            }
+            let packet = self.pending.pop_front().unwrap();
+            write_packet(&mut self.inbound, packet).expect("packet checked on creation");
+        }
+    }
+}
+
+impl AsyncRead for ReplayTransport {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.release();
+        if self.inbound.is_empty() && !self.pending.is_empty() {
+            self.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let len = buf.len().min(self.inbound.len());
+        let bytes = self.inbound.split_to(len);
+        buf[..len].copy_from_slice(&bytes[..]);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl AsyncWrite for ReplayTransport {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.outbound.extend_from_slice(buf);
+        loop {
+            match read_packet(&mut self.outbound) {
+                Ok(Some(packet)) => {
+                    if packet.seq.kind() == PacketKind::Request {
+                        self.written.push(packet.seq);
+                    }
+                }
+                Ok(None) => break,
+                Err(err) => {
+                    let err = io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", err));
+                    return Poll::Ready(Err(err));
+                }
+            }
+        }
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream::StreamExt;
+    use futures_util::task::noop_waker;
+
+    use super::*;
+    use crate::client::EventHandler;
+    use crate::conn::capture::CaptureWriter;
+    use crate::conn::testing::TestExecutor;
+    use crate::conn::{ConnectionBuilder, Role, Word};
+    use crate::event::ServerEvent;
+    use crate::profile::GameProfile;
+
+    fn packet(kind: PacketKind, num: u32, word: &str) -> Packet {
+        let seq = PacketSequence::new(kind, Role::Client, num).unwrap();
+        Packet::new(seq, vec![Word::new(word).unwrap()])
+    }
+
+    fn read(transport: &mut ReplayTransport, len: usize) -> Option<Vec<u8>> {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut buf = vec![0u8; len];
+        match Pin::new(transport).poll_read(&mut cx, &mut buf[..]) {
+            Poll::Ready(Ok(n)) => Some(buf[..n].to_vec()),
+            Poll::Pending => None,
+            other => panic!("unexpected poll: {:?}", other),
+        }
+    }
+
+    fn write(transport: &mut ReplayTransport, bytes: &[u8]) {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        match Pin::new(transport).poll_write(&mut cx, bytes) {
+            Poll::Ready(Ok(n)) => assert_eq!(n, bytes.len()),
+            other => panic!("unexpected poll: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn replay_test() {
+        let records = vec![
+            Record::now(
+                Direction::Outbound,
+                packet(PacketKind::Request, 0, "serverInfo"),
+            ),
+            Record::now(Direction::Inbound, packet(PacketKind::Response, 0, "OK")),
+            Record::now(
+                Direction::Outbound,
+                packet(PacketKind::Request, 1, "version"),
+            ),
+            Record::now(Direction::Inbound, packet(PacketKind::Response, 1, "BF4")),
+        ];
+        let mut transport = ReplayTransport::from_records(records).unwrap();
+        let request = |num, word| {
+            let mut bytes = BytesMut::new();
+            write_packet(&mut bytes, packet(PacketKind::Request, num, word)).unwrap();
+            bytes
+        };
+
+        // Responses wait for their request, which may be written in parts.
+        assert_eq!(read(&mut transport, 1024), None);
+        let first = request(0, "serverInfo");
+        write(&mut transport, &first[..5]);
+        assert_eq!(read(&mut transport, 1024), None);
+        write(&mut transport, &first[5..]);
+        let mut bytes = BytesMut::from(&read(&mut transport, 5).unwrap()[..]);
+        bytes.extend_from_slice(&read(&mut transport, 1024).unwrap()[..]);
+        assert_eq!(read(&mut transport, 1024), None);
+        write(&mut transport, &request(1, "version")[..]);
+        bytes.extend_from_slice(&read(&mut transport, 1024).unwrap()[..]);
+        assert_eq!(read(&mut transport, 1024), Some(Vec::new()));
+
+        let first = read_packet(&mut bytes).unwrap().unwrap();
+        let second = read_packet(&mut bytes).unwrap().unwrap();
+        assert_eq!(first.words[0].as_str(), "OK");
+        assert_eq!(second.seq.number(), 1);
+        assert_eq!(second.words[0].as_str(), "BF4");
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn replay_connection_test() {
+        let exec = TestExecutor::new();
+        let event = |num, word| {
+            let seq = PacketSequence::new(PacketKind::Request, Role::Server, num).unwrap();
+            Packet::new(seq, vec![Word::new(word).unwrap()])
+        };
+        let records = vec![
+            Record::now(Direction::Inbound, event(0, "player.onJoin")),
+            Record::now(
+                Direction::Outbound,
+                packet(PacketKind::Request, 0, "serverInfo"),
+            ),
+            Record::now(Direction::Inbound, packet(PacketKind::Response, 0, "OK")),
+            Record::now(Direction::Inbound, event(1, "player.onLeave")),
+        ];
+        let transport = ReplayTransport::from_records(records).unwrap();
+        let (handler, events) = EventHandler::new(GameProfile::Bf4);
+        let mut events = events.subscribe();
+        let mut conn = ConnectionBuilder::new()
+            .handler(handler)
+            .with_transport_and_exec(transport, Role::Client, exec.clone())
+            .unwrap();
+
+        let words = |event| match event {
+            Some(ServerEvent::Other(body)) => body.words()[0].as_str().to_owned(),
+            event => panic!("unexpected event {:?}", event),
+        };
+        assert_eq!(words(exec.block_on(events.next())), "player.onJoin");
+        let body = exec.block_on(conn.send(vec!["serverInfo"])).unwrap();
+        assert_eq!(body.words()[0].as_str(), "OK");
+        assert_eq!(words(exec.block_on(events.next())), "player.onLeave");
+    }
+
+    #[test]
+    fn replay_capture_test() {
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        writer
+            .write_record(&Record::now(
+                Direction::Inbound,
+                packet(PacketKind::Request, 3, "player.onJoin"),
+            ))
+            .unwrap();
+        let capture = writer.into_inner();
+        let mut transport =
+            ReplayTransport::new(CaptureReader::new(&capture[..]).unwrap()).unwrap();
+        let mut bytes = BytesMut::from(&read(&mut transport, 1024).unwrap()[..]);
+        let packet = read_packet(&mut bytes).unwrap().unwrap();
+        assert_eq!(packet.words[0].as_str(), "player.onJoin");
+    }
+}
@@ -43,7 +43,7 @@ impl Sender {
     pub fn send(&mut self, request: Request) -> ResponseFuture {
         let (response_tx, response_rx) = oneshot::channel();
         let responable = Respondable {
-            request: request,
+            request,
             responder: response_tx,
         };
         if self.tx.unbounded_send(responable).is_ok() {
@@ -56,7 +56,7 @@ impl Sender {
     }
 
     pub fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), Error>> {
-        self.tx.poll_ready(cx).map_err(|err| Error::Responder(err))
+        self.tx.poll_ready(cx).map_err(Error::Responder)
     }
 }
 
@@ -2,18 +2,27 @@ use std::io;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
-use bytes::BytesMut;
+use bytes::{Buf, BytesMut};
 use futures_util::ready;
 use futures_util::sink::Sink;
 use futures_util::stream::{FusedStream, Stream};
-use tokio_codec::{Decoder, Encoder, Framed};
 use tokio_io::{AsyncRead, AsyncWrite};
 
-use super::packet::{read_packet, write_packet, Packet, PacketError};
+use super::capture::{Direction, Recorder};
+use super::packet::{read_packet, write_packet, Packet, PACKET_MAX_SIZE};
+use super::trace::Tracer;
+use super::SocketError;
+
+/// The most bytes read from the transport at once.
+const READ_CHUNK_SIZE: usize = 4096;
 
 pub struct Socket<T: AsyncRead + AsyncWrite> {
-    inner: Framed<T, PacketCodec>,
+    inner: T,
+    read_buf: BytesMut,
+    write_buf: BytesMut,
+    eof: bool,
     broken: bool,
+    observer: Observer,
 }
 
 impl<T> Socket<T>
@@ -22,18 +31,67 @@ where
 {
     pub fn new(inner: T) -> Self {
         Self {
-            inner: Framed::new(inner, PacketCodec),
+            inner,
+            read_buf: BytesMut::new(),
+            write_buf: BytesMut::new(),
+            eof: false,
             broken: false,
+            observer: Observer::default(),
         }
     }
 
-    fn get_pinned_inner(&mut self) -> Result<Pin<&mut Framed<T, PacketCodec>>, SocketError> {
+    /// Traces every inbound and outbound packet with the tracer.
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        self.observer.tracer = Some(tracer);
+    }
+
+    /// Records every inbound and outbound packet with the recorder.
+    pub fn set_recorder(&mut self, recorder: Recorder) {
+        self.observer.recorder = Some(recorder);
+    }
+
+    fn check_broken(&self) -> Result<(), SocketError> {
         if self.is_terminated() {
             Err(SocketError::Broken)
         } else {
-            Ok(Pin::new(&mut self.inner))
+            Ok(())
         }
     }
+
+    /// Reads the next packet, reading from the transport as needed.
+    fn poll_read_packet(&mut self, cx: &mut Context) -> Poll<Option<Result<Packet, SocketError>>> {
+        loop {
+            if let Some(packet) = read_packet(&mut self.read_buf)? {
+                return Poll::Ready(Some(Ok(packet)));
+            }
+            if self.eof {
+                if self.read_buf.is_empty() {
+                    return Poll::Ready(None);
+                }
+                let err = io::Error::from(io::ErrorKind::UnexpectedEof);
+                return Poll::Ready(Some(Err(err.into())));
+            }
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            let read = ready!(Pin::new(&mut self.inner).poll_read(cx, &mut chunk))?;
+            if read == 0 {
+                self.eof = true;
+            }
+            self.read_buf.extend_from_slice(&chunk[..read]);
+        }
+    }
+
+    /// Writes out every buffered packet.
+    fn poll_write_buf(&mut self, cx: &mut Context) -> Poll<Result<(), SocketError>> {
+        while !self.write_buf.is_empty() {
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.write_buf))?;
+            if written == 0 {
+                let err = io::Error::from(io::ErrorKind::WriteZero);
+                return Poll::Ready(Err(err.into()));
+            }
+            self.write_buf.advance(written);
+        }
+        Poll::Ready(Ok(()))
+    }
 }
 
 impl<T> Stream for Socket<T>
@@ -46,12 +104,14 @@ where
         mut self: Pin<&mut Self>,
         cx: &mut Context,
     ) -> Poll<Option<Result<Packet, SocketError>>> {
-        let res = match self.get_pinned_inner() {
-            Ok(inner) => ready!(inner.poll_next(cx)),
-            Err(_) => return Poll::Ready(None),
-        };
-        if res.as_ref().map_or(false, Result::is_err) {
-            self.broken = true;
+        if self.is_terminated() {
+            return Poll::Ready(None);
+        }
+        let res = ready!(self.poll_read_packet(cx));
+        match &res {
+            Some(Ok(packet)) => self.observer.observe(Direction::Inbound, packet),
+            Some(Err(_)) => self.broken = true,
+            None => {}
         }
         Poll::Ready(res)
     }
@@ -73,72 +133,158 @@ where
     type Error = SocketError;
 
     fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
-        match self.get_pinned_inner() {
-            Ok(inner) => inner.poll_ready(cx),
-            Err(err) => Poll::Ready(Err(err)),
+        self.check_broken()?;
+        // Write out buffered packets before taking more in.
+        if self.write_buf.len() >= PACKET_MAX_SIZE {
+            ready!(self.poll_write_buf(cx))?;
         }
+        Poll::Ready(Ok(()))
     }
 
     fn start_send(mut self: Pin<&mut Self>, item: Packet) -> Result<(), Self::Error> {
-        self.get_pinned_inner()
-            .and_then(|inner| inner.start_send(item))
+        self.check_broken()?;
+        self.observer.observe(Direction::Outbound, &item);
+        Ok(write_packet(&mut self.write_buf, item)?)
     }
 
     fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
-        match self.get_pinned_inner() {
-            Ok(inner) => inner.poll_flush(cx),
-            Err(err) => Poll::Ready(Err(err)),
-        }
+        self.check_broken()?;
+        ready!(self.poll_write_buf(cx))?;
+        Poll::Ready(Ok(ready!(Pin::new(&mut self.inner).poll_flush(cx))?))
     }
 
     fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
-        match self.get_pinned_inner() {
-            Ok(inner) => inner.poll_close(cx),
-            Err(err) => Poll::Ready(Err(err)),
-        }
+        ready!(self.as_mut().poll_flush(cx))?;
+        Poll::Ready(Ok(ready!(Pin::new(&mut self.inner).poll_shutdown(cx))?))
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
-#[derive(Debug)]
-pub enum SocketError {
-    Broken,
-    Closed,
-    Io(io::Error),
-    Packet(PacketError),
+/// The tracer and recorder packets are shown to.
+#[derive(Debug, Default)]
+pub(crate) struct Observer {
+    pub tracer: Option<Tracer>,
+    pub recorder: Option<Recorder>,
 }
 
-impl From<io::Error> for SocketError {
-    fn from(err: io::Error) -> Self {
-        Self::Io(err)
+impl Observer {
+    /// Shows a packet to the tracer and recorder. A failing recorder is
+    /// dropped, so as not to break the connection.
+    pub fn observe(&mut self, direction: Direction, packet: &Packet) {
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.trace(direction, packet);
+        }
+        let recording = match self.recorder.as_mut() {
+            Some(recorder) => recorder.record(direction, packet),
+            None => true,
+        };
+        if !recording {
+            self.recorder = None;
+        }
     }
 }
 
-impl From<PacketError> for SocketError {
-    fn from(err: PacketError) -> Self {
-        Self::Packet(err)
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use futures_util::task::noop_waker;
+
+    use super::*;
+    use crate::conn::{PacketKind, PacketSequence, Role, Word};
+
+    /// A transport reading a byte at a time.
+    struct Trickle {
+        inbound: BytesMut,
+        outbound: Vec<u8>,
     }
-}
 
-///////////////////////////////////////////////////////////////////////////////
+    impl AsyncRead for Trickle {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            let len = buf.len().min(self.inbound.len()).min(1);
+            buf[..len].copy_from_slice(&self.inbound.split_to(len));
+            Poll::Ready(Ok(len))
+        }
+    }
 
-struct PacketCodec;
+    impl AsyncWrite for Trickle {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.outbound.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
 
-impl Encoder for PacketCodec {
-    type Item = Packet;
-    type Error = SocketError;
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
 
-    fn encode(&mut self, packet: Self::Item, buf: &mut BytesMut) -> Result<(), Self::Error> {
-        Ok(write_packet(buf, packet)?)
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
     }
-}
 
-impl Decoder for PacketCodec {
-    type Item = Packet;
-    type Error = SocketError;
+    fn packet(seq: u32, word: &str) -> Packet {
+        let seq = PacketSequence::new(PacketKind::Request, Role::Client, seq).unwrap();
+        Packet::new(seq, vec![Word::new(word).unwrap()])
+    }
+
+    #[test]
+    fn framing_test() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut inbound = BytesMut::new();
+        write_packet(&mut inbound, packet(1, "version")).unwrap();
+        write_packet(&mut inbound, packet(2, "serverInfo")).unwrap();
+        // The start of a third packet, cut short by EOF.
+        inbound.extend_from_slice(&[3, 0]);
+        let mut socket = Socket::new(Trickle {
+            inbound,
+            outbound: Vec::new(),
+        });
 
-    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        Ok(read_packet(buf)?)
+        let mut next = || match Pin::new(&mut socket).poll_next(&mut cx) {
+            Poll::Ready(next) => next,
+            Poll::Pending => panic!("socket is pending"),
+        };
+        assert_eq!(next().unwrap().unwrap().words, packet(1, "version").words);
+        assert_eq!(
+            next().unwrap().unwrap().words,
+            packet(2, "serverInfo").words
+        );
+        match next() {
+            Some(Err(SocketError::Io(err))) => assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof),
+            other => panic!("expected EOF, got {:?}", other),
+        }
+        assert!(next().is_none());
+        assert!(socket.is_terminated());
+
+        let mut socket = Socket::new(Trickle {
+            inbound: BytesMut::new(),
+            outbound: Vec::new(),
+        });
+        let mut expected = BytesMut::new();
+        write_packet(&mut expected, packet(1, "version")).unwrap();
+        Pin::new(&mut socket)
+            .start_send(packet(1, "version"))
+            .unwrap();
+        assert!(socket.inner.outbound.is_empty());
+        match Pin::new(&mut socket).poll_flush(&mut cx) {
+            Poll::Ready(res) => res.unwrap(),
+            Poll::Pending => panic!("socket is pending"),
+        }
+        assert_eq!(socket.inner.outbound, &expected[..]);
+        // A clean EOF ends the stream.
+        assert!(matches!(
+            Pin::new(&mut socket).poll_next(&mut cx),
+            Poll::Ready(None)
+        ));
     }
 }
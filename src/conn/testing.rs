@@ -0,0 +1,142 @@
+//! Helpers for testing connections against an in-memory server.
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use bytes::BytesMut;
+use futures_util::task::noop_waker;
+use tokio_executor::{Executor, SpawnError};
+use tokio_io::{AsyncRead, AsyncWrite};
+
+use super::protocol::Event;
+use super::{Body, Protocol, Role};
+
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// An executor running spawned futures alongside `block_on`.
+///
+/// Futures are polled in turn until the blocked on future completes,
+/// which suits transports that never block, such as [`FakeServer`].
+#[derive(Clone, Default)]
+pub struct TestExecutor {
+    spawned: Arc<Mutex<Vec<BoxFuture>>>,
+}
+
+impl TestExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        for _ in 0..10_000 {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            // Tasks may spawn others, so the lock is not held while polling.
+            let tasks: Vec<BoxFuture> = self.spawned.lock().unwrap().drain(..).collect();
+            for mut task in tasks {
+                if task.as_mut().poll(&mut cx).is_pending() {
+                    self.spawned.lock().unwrap().push(task);
+                }
+            }
+        }
+        panic!("future did not complete");
+    }
+}
+
+impl Executor for TestExecutor {
+    fn spawn(&mut self, future: BoxFuture) -> Result<(), SpawnError> {
+        self.spawned.lock().unwrap().push(future);
+        Ok(())
+    }
+}
+
+type Responder = Box<dyn FnMut(&Body) -> Option<Body> + Send>;
+
+/// A transport answering requests as a server would.
+///
+/// Requests written to the transport are answered with the body the
+/// responder returns, or left unanswered on `None`. Events can be sent
+/// to the client with [`FakeServer::send_event`].
+pub struct FakeServer {
+    protocol: Protocol,
+    responder: Responder,
+    requests: Arc<Mutex<Vec<Body>>>,
+    outbound: BytesMut,
+}
+
+impl FakeServer {
+    pub fn new<F>(responder: F) -> Self
+    where
+        F: FnMut(&Body) -> Option<Body> + Send + 'static,
+    {
+        Self {
+            protocol: Protocol::new(Role::Server),
+            responder: Box::new(responder),
+            requests: Default::default(),
+            outbound: BytesMut::new(),
+        }
+    }
+
+    /// Returns the requests received so far, shared with the server.
+    pub fn requests(&self) -> Arc<Mutex<Vec<Body>>> {
+        self.requests.clone()
+    }
+
+    /// Queues a request from the server, such as an event.
+    pub fn send_event(&mut self, body: Body) {
+        self.protocol.send_request(body).unwrap();
+        self.protocol.write_transmit(&mut self.outbound).unwrap();
+    }
+}
+
+impl AsyncRead for FakeServer {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.outbound.is_empty() {
+            return Poll::Pending;
+        }
+        let len = buf.len().min(self.outbound.len());
+        let bytes = self.outbound.split_to(len);
+        buf[..len].copy_from_slice(&bytes[..]);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl AsyncWrite for FakeServer {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+        this.protocol.receive_bytes(buf).unwrap();
+        while let Some(event) = this.protocol.poll_event() {
+            if let Event::Request { seq, body } = event {
+                this.requests.lock().unwrap().push(body.clone());
+                if let Some(response) = (this.responder)(&body) {
+                    this.protocol.respond(seq, response).unwrap();
+                }
+            }
+        }
+        this.protocol.write_transmit(&mut this.outbound).unwrap();
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,286 @@
+//! Human-readable packet tracing.
+
+use std::fmt::{self, Write};
+
+use bytes::BytesMut;
+
+use super::capture::Direction;
+use super::packet::{
+    is_valid_word_char, read_packet, write_packet, PacketError, PACKET_HEADER_SIZE,
+    PACKET_MAX_SIZE, PACKET_MAX_WORDS,
+};
+use super::Packet;
+
+const HEX_DUMP_WIDTH: usize = 16;
+
+/// Formats a packet in a compact, single line form, prefixed with
+/// the direction it travelled.
+pub fn format_packet(direction: Direction, packet: &Packet) -> String {
+    let arrow = match direction {
+        Direction::Inbound => "<-",
+        Direction::Outbound => "->",
+    };
+    format!("{} {}", arrow, packet)
+}
+
+/// Formats bytes as a hex dump, with offsets and an ASCII column.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(HEX_DUMP_WIDTH).enumerate() {
+        let _ = write!(out, "{:08x} ", line * HEX_DUMP_WIDTH);
+        for i in 0..HEX_DUMP_WIDTH {
+            match chunk.get(i) {
+                Some(byte) => {
+                    let _ = write!(out, " {:02x}", byte);
+                }
+                None => out.push_str("   "),
+            }
+        }
+        out.push_str("  |");
+        for byte in chunk {
+            if byte.is_ascii_graphic() || *byte == b' ' {
+                out.push(*byte as char);
+            } else {
+                out.push('.');
+            }
+        }
+        out.push_str("|\n");
+    }
+    out
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Traces packets passing through a `Socket`.
+pub struct Tracer {
+    sink: Box<dyn FnMut(&str) + Send>,
+    hex_dump: bool,
+}
+
+impl Tracer {
+    /// Creates a tracer, passing each trace line to the sink.
+    pub fn new<F>(sink: F) -> Self
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        Self {
+            sink: Box::new(sink),
+            hex_dump: false,
+        }
+    }
+
+    /// Creates a tracer writing to stderr.
+    pub fn stderr() -> Self {
+        Self::new(|line| eprintln!("{}", line))
+    }
+
+    /// Sets whether to follow each packet with a hex dump of its frame.
+    pub fn hex_dump(mut self, enabled: bool) -> Self {
+        self.hex_dump = enabled;
+        self
+    }
+
+    /// Traces a packet.
+    pub fn trace(&mut self, direction: Direction, packet: &Packet) {
+        (self.sink)(&format_packet(direction, packet));
+        if self.hex_dump {
+            let mut frame = BytesMut::with_capacity(packet.byte_size());
+            if write_packet(&mut frame, packet.clone()).is_ok() {
+                (self.sink)(hex_dump(&frame[..]).trim_end());
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Tracer {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Tracer")
+            .field("hex_dump", &self.hex_dump)
+            .finish()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// A frame decoded from an arbitrary byte capture.
+#[derive(Debug)]
+pub enum Frame {
+    /// A well formed packet starting at the offset.
+    Packet { offset: usize, packet: Packet },
+    /// A malformed frame starting at the offset, which failed to decode
+    /// at `error_offset`.
+    Malformed {
+        offset: usize,
+        error_offset: usize,
+        error: PacketError,
+    },
+    /// Trailing bytes at the offset, too few to form a complete frame.
+    Incomplete { offset: usize, len: usize },
+}
+
+/// Decodes an arbitrary byte capture into frames.
+///
+/// Decoding continues past a malformed frame if its size could be read,
+/// otherwise decoding stops at the malformed frame.
+pub fn decode_frames(bytes: &[u8]) -> Vec<Frame> {
+    let mut frames = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let rest = &bytes[offset..];
+        let mut buf = BytesMut::from(rest);
+        match read_packet(&mut buf) {
+            Ok(Some(packet)) => {
+                frames.push(Frame::Packet { offset, packet });
+                offset += rest.len() - buf.len();
+            }
+            Ok(None) => {
+                frames.push(Frame::Incomplete {
+                    offset,
+                    len: rest.len(),
+                });
+                break;
+            }
+            Err(error) => {
+                let (error_offset, frame_size) = locate_malformed(rest);
+                frames.push(Frame::Malformed {
+                    offset,
+                    error_offset: offset + error_offset,
+                    error,
+                });
+                match frame_size {
+                    Some(frame_size) => offset += frame_size,
+                    None => break,
+                }
+            }
+        }
+    }
+    frames
+}
+
+/// Decodes an arbitrary byte capture and pretty-prints its frames,
+/// one per line, prefixed with their offset.
+pub fn pretty_print(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for frame in decode_frames(bytes) {
+        let _ = match frame {
+            Frame::Packet { offset, packet } => writeln!(out, "{:08x}: {}", offset, packet),
+            Frame::Malformed {
+                offset,
+                error_offset,
+                error,
+            } => writeln!(
+                out,
+                "{:08x}: malformed frame ({:?}) at offset {:08x}",
+                offset, error, error_offset
+            ),
+            Frame::Incomplete { offset, len } => {
+                writeln!(out, "{:08x}: incomplete frame of {} bytes", offset, len)
+            }
+        };
+    }
+    out
+}
+
+/// Walks a malformed frame, returning the offset of the first invalid
+/// byte and the frame size if the frame's size field was valid.
+fn locate_malformed(frame: &[u8]) -> (usize, Option<usize>) {
+    let read_u32 = |offset: usize| {
+        let mut raw = [0u8; 4];
+        raw.copy_from_slice(&frame[offset..offset + 4]);
+        u32::from_le_bytes(raw) as usize
+    };
+    let size = read_u32(4);
+    if !(PACKET_HEADER_SIZE..=PACKET_MAX_SIZE).contains(&size) {
+        return (4, None);
+    }
+    let word_count = read_u32(8);
+    if word_count > PACKET_MAX_WORDS {
+        return (8, Some(size));
+    }
+    let end = size.min(frame.len());
+    let mut pos = PACKET_HEADER_SIZE;
+    for _ in 0..word_count {
+        if pos + 4 > end {
+            return (pos, Some(size));
+        }
+        let word_size = read_u32(pos);
+        let content = pos + 4;
+        if content + word_size + 1 > end {
+            return (pos, Some(size));
+        }
+        let word = &frame[content..content + word_size];
+        if let Some(i) = word.iter().position(|b| !is_valid_word_char(*b)) {
+            return (content + i, Some(size));
+        }
+        if frame[content + word_size] != 0 {
+            return (content + word_size, Some(size));
+        }
+        pos = content + word_size + 1;
+    }
+    (pos, Some(size))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conn::{PacketKind, PacketSequence, Role, Word};
+
+    #[test]
+    fn format_packet_test() {
+        let seq = PacketSequence::new(PacketKind::Request, Role::Server, 12).unwrap();
+        let words = vec![
+            Word::new("player.onJoin").unwrap(),
+            Word::new("a b").unwrap(),
+        ];
+        let packet = Packet::new(seq, words);
+        assert_eq!(
+            format_packet(Direction::Inbound, &packet),
+            r#"<- #12 server request "player.onJoin" "a b""#
+        );
+    }
+
+    #[test]
+    fn hex_dump_test() {
+        assert_eq!(
+            hex_dump(b"OK\x00"),
+            "00000000  4f 4b 00                                         |OK.|\n"
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn decode_malformed_frame_test() {
+        let mut bytes = vec![
+            // seq
+            0, 0, 0, 0x80,
+            // size
+            21, 0, 0, 0,
+            // word num
+            1, 0, 0, 0,
+            // word "ok", with a missing NULL terminator
+            4, 0, 0, 0, b'o', b'k', b'k', b'k', 1,
+        ];
+        let mut good = BytesMut::new();
+        let seq = PacketSequence::new(PacketKind::Response, Role::Client, 0).unwrap();
+        write_packet(&mut good, Packet::new(seq, vec![Word::new("OK").unwrap()])).unwrap();
+        bytes.extend_from_slice(&good[..]);
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let frames = decode_frames(&bytes[..]);
+        assert_eq!(frames.len(), 3);
+        match frames[0] {
+            Frame::Malformed { offset: 0, error_offset: 20, .. } => (),
+            ref other => panic!("unexpected frame: {:?}", other),
+        }
+        match frames[1] {
+            Frame::Packet { offset: 21, ref packet } => assert_eq!(packet.words[0].as_str(), "OK"),
+            ref other => panic!("unexpected frame: {:?}", other),
+        }
+        match frames[2] {
+            Frame::Incomplete { len: 3, .. } => (),
+            ref other => panic!("unexpected frame: {:?}", other),
+        }
+    }
+}
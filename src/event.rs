@@ -0,0 +1,296 @@
+//! Parsing of the events servers send once enabled.
+
+use crate::conn::{Body, BodyError, WordReader};
+use crate::profile::GameProfile;
+use crate::types::{PlayerGuid, PlayerList, PlayerName, PlayerSubset, SquadId, TeamId, TeamScores};
+
+/// An event sent by the server.
+///
+/// Words following the known arguments of an event are ignored, as
+/// game patches may append arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerEvent {
+    /// A player completed authentication, and is about to join.
+    PlayerAuthenticated {
+        name: PlayerName,
+    },
+    /// A player connected. BC2 does not send the GUID.
+    PlayerJoin {
+        name: PlayerName,
+        guid: Option<PlayerGuid>,
+    },
+    /// A player disconnected, with their final scores.
+    PlayerLeave {
+        name: PlayerName,
+        info: PlayerList,
+    },
+    PlayerSpawn {
+        name: PlayerName,
+        team_id: TeamId,
+    },
+    /// A player was killed. The killer is `None` for deaths not caused
+    /// by a player, such as falling.
+    PlayerKill {
+        killer: Option<PlayerName>,
+        victim: PlayerName,
+        weapon: String,
+        headshot: bool,
+    },
+    /// A chat message, from a player or `Server`. Older servers do not
+    /// send the audience.
+    PlayerChat {
+        source: String,
+        message: String,
+        subset: Option<PlayerSubset>,
+    },
+    PlayerSquadChange {
+        name: PlayerName,
+        team_id: TeamId,
+        squad_id: SquadId,
+    },
+    PlayerTeamChange {
+        name: PlayerName,
+        team_id: TeamId,
+        squad_id: SquadId,
+    },
+    /// Output of PunkBuster, see [`crate::punkbuster`].
+    PunkBusterMessage {
+        text: String,
+    },
+    /// A level was loaded. BC2 does not send the game mode.
+    LevelLoaded {
+        level: String,
+        game_mode: Option<String>,
+        rounds_played: u32,
+        rounds_total: u32,
+    },
+    RoundOver {
+        winning_team: TeamId,
+    },
+    RoundOverPlayers {
+        players: PlayerList,
+    },
+    RoundOverTeamScores {
+        scores: TeamScores,
+    },
+    MaxPlayerCountChange {
+        count: u32,
+    },
+    /// An event not known to this crate.
+    Other(Body),
+}
+
+impl ServerEvent {
+    /// Parses the body of an event request in the game's dialect.
+    pub fn parse(body: &Body, profile: GameProfile) -> Result<Self, BodyError> {
+        let mut reader = body.reader();
+        let name = reader.next_word()?.as_str();
+        let reader = &mut reader;
+        let event = match name {
+            "player.onAuthenticated" => ServerEvent::PlayerAuthenticated {
+                name: reader.parse()?,
+            },
+            "player.onJoin" => ServerEvent::PlayerJoin {
+                name: reader.parse()?,
+                guid: if profile.join_has_guid() {
+                    reader.parse()?
+                } else {
+                    None
+                },
+            },
+            "player.onLeave" => ServerEvent::PlayerLeave {
+                name: reader.parse()?,
+                info: reader.decode()?,
+            },
+            "player.onSpawn" => ServerEvent::PlayerSpawn {
+                name: reader.parse()?,
+                team_id: reader.parse()?,
+            },
+            "player.onKill" => ServerEvent::PlayerKill {
+                killer: reader.parse()?,
+                victim: reader.parse()?,
+                weapon: reader.parse()?,
+                headshot: reader.parse()?,
+            },
+            "player.onChat" => ServerEvent::PlayerChat {
+                source: reader.parse()?,
+                message: reader.parse()?,
+                subset: optional_subset(reader)?,
+            },
+            "player.onSquadChange" => ServerEvent::PlayerSquadChange {
+                name: reader.parse()?,
+                team_id: reader.parse()?,
+                squad_id: reader.parse()?,
+            },
+            "player.onTeamChange" => ServerEvent::PlayerTeamChange {
+                name: reader.parse()?,
+                team_id: reader.parse()?,
+                squad_id: reader.parse()?,
+            },
+            "punkBuster.onMessage" => ServerEvent::PunkBusterMessage {
+                text: reader.parse()?,
+            },
+            "server.onLevelLoaded" => ServerEvent::LevelLoaded {
+                level: reader.parse()?,
+                game_mode: Some(reader.parse()?),
+                rounds_played: reader.parse()?,
+                rounds_total: reader.parse()?,
+            },
+            "server.onLoadingLevel" => ServerEvent::LevelLoaded {
+                level: reader.parse()?,
+                game_mode: None,
+                rounds_played: reader.parse()?,
+                rounds_total: reader.parse()?,
+            },
+            "server.onRoundOver" => ServerEvent::RoundOver {
+                winning_team: reader.parse()?,
+            },
+            "server.onRoundOverPlayers" => ServerEvent::RoundOverPlayers {
+                players: reader.decode()?,
+            },
+            "server.onRoundOverTeamScores" => ServerEvent::RoundOverTeamScores {
+                scores: reader.decode()?,
+            },
+            "server.onMaxPlayerCountChange" => ServerEvent::MaxPlayerCountChange {
+                count: reader.parse()?,
+            },
+            _ => ServerEvent::Other(body.clone()),
+        };
+        Ok(event)
+    }
+}
+
+fn optional_subset(reader: &mut WordReader<'_>) -> Result<Option<PlayerSubset>, BodyError> {
+    if reader.is_empty() {
+        Ok(None)
+    } else {
+        reader.decode().map(Some)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUID: &str = "EA_0123456789ABCDEF0123456789ABCDEF";
+
+    fn parse(words: &[&str], profile: GameProfile) -> Result<ServerEvent, BodyError> {
+        ServerEvent::parse(&Body::new(words.to_vec()).unwrap(), profile)
+    }
+
+    fn name(name: &str) -> PlayerName {
+        PlayerName::new(name).unwrap()
+    }
+
+    #[test]
+    fn player_events_test() {
+        assert_eq!(
+            parse(&["player.onJoin", "avitex", GUID], GameProfile::Bf4),
+            Ok(ServerEvent::PlayerJoin {
+                name: name("avitex"),
+                guid: Some(PlayerGuid::new(GUID).unwrap()),
+            })
+        );
+        assert_eq!(
+            parse(&["player.onJoin", "avitex"], GameProfile::Bc2),
+            Ok(ServerEvent::PlayerJoin {
+                name: name("avitex"),
+                guid: None,
+            })
+        );
+        assert_eq!(
+            parse(
+                &["player.onKill", "", "avitex", "SoldierCollision", "false"],
+                GameProfile::Bf3
+            ),
+            Ok(ServerEvent::PlayerKill {
+                killer: None,
+                victim: name("avitex"),
+                weapon: "SoldierCollision".to_string(),
+                headshot: false,
+            })
+        );
+        assert_eq!(
+            parse(&["player.onChat", "Server", "hi"], GameProfile::Bc2),
+            Ok(ServerEvent::PlayerChat {
+                source: "Server".to_string(),
+                message: "hi".to_string(),
+                subset: None,
+            })
+        );
+        assert_eq!(
+            parse(
+                &["player.onChat", "avitex", "hi", "team", "1"],
+                GameProfile::Bf4
+            ),
+            Ok(ServerEvent::PlayerChat {
+                source: "avitex".to_string(),
+                message: "hi".to_string(),
+                subset: Some(PlayerSubset::Team(TeamId::new(1).unwrap())),
+            })
+        );
+    }
+
+    #[test]
+    fn server_events_test() {
+        assert_eq!(
+            parse(
+                &[
+                    "server.onLevelLoaded",
+                    "MP_Prison",
+                    "ConquestLarge0",
+                    "0",
+                    "2"
+                ],
+                GameProfile::Bf4
+            ),
+            Ok(ServerEvent::LevelLoaded {
+                level: "MP_Prison".to_string(),
+                game_mode: Some("ConquestLarge0".to_string()),
+                rounds_played: 0,
+                rounds_total: 2,
+            })
+        );
+        let leave = [
+            "player.onLeave",
+            "avitex",
+            "8",
+            "name",
+            "guid",
+            "teamId",
+            "squadId",
+            "kills",
+            "deaths",
+            "score",
+            "ping",
+            "1",
+            "avitex",
+            "",
+            "1",
+            "0",
+            "3",
+            "4",
+            "100",
+            "20",
+        ];
+        match parse(&leave, GameProfile::Bc2).unwrap() {
+            ServerEvent::PlayerLeave { info, .. } => assert_eq!(info.players[0].kills, 3),
+            event => panic!("unexpected event {:?}", event),
+        }
+        // Player info blocks are read whichever columns they carry.
+        match parse(&leave, GameProfile::Bf4).unwrap() {
+            ServerEvent::PlayerLeave { info, .. } => assert_eq!(info.players[0].rank, None),
+            event => panic!("unexpected event {:?}", event),
+        }
+        match parse(&["server.onUnknown", "x"], GameProfile::Bf4).unwrap() {
+            ServerEvent::Other(body) => assert_eq!(body.len(), 2),
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert_eq!(
+            parse(&["server.onRoundOver"], GameProfile::Bf4),
+            Err(BodyError::MissingWord(1))
+        );
+    }
+}
@@ -1,2 +1,11 @@
+pub mod catalogue;
+#[cfg(feature = "async")]
+pub mod client;
 pub mod conn;
-pub mod types;
\ No newline at end of file
+pub mod event;
+pub mod profile;
+pub mod punkbuster;
+pub mod resolve;
+pub mod roster;
+pub mod round;
+pub mod types;
@@ -0,0 +1,182 @@
+//! Differences between the Frostbite RCON dialects.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::types::Version;
+
+/// The duration of `admin.yell` on BC2 when none is given.
+const BC2_DEFAULT_YELL_DURATION: Duration = Duration::from_secs(5);
+/// The duration of `admin.yell` on later games when none is given.
+const DEFAULT_YELL_DURATION: Duration = Duration::from_secs(10);
+
+/// Commands only BF4 and Hardline servers know. Entries ending with a
+/// dot are command families.
+const BF4_ONLY_COMMANDS: &[&str] = &[
+    "currentLevel",
+    "player.ping",
+    "player.idleDuration",
+    "player.isAlive",
+    "spectatorList.",
+    "gameAdmin.",
+];
+
+/// Commands BC2 servers do not know, besides the BF4 only ones.
+const BC2_MISSING_COMMANDS: &[&str] = &[
+    "squad.",
+    "reservedSlotsList.aggressiveJoin",
+    "mapList.getMapIndices",
+    "mapList.setNextMapIndex",
+    "mapList.runNextRound",
+    "mapList.restartRound",
+    "mapList.endRound",
+];
+
+const BC2_PLAYER_COLUMNS: &[&str] = &[
+    "name", "guid", "teamId", "squadId", "kills", "deaths", "score", "ping",
+];
+const BF3_PLAYER_COLUMNS: &[&str] = &[
+    "name", "guid", "teamId", "squadId", "kills", "deaths", "score", "rank", "ping",
+];
+const BF4_PLAYER_COLUMNS: &[&str] = &[
+    "name", "guid", "teamId", "squadId", "kills", "deaths", "score", "rank", "ping", "type",
+];
+
+/// The dialect of RCON spoken by a game server, BF4 by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GameProfile {
+    /// Battlefield: Bad Company 2
+    Bc2,
+    /// Battlefield 3
+    Bf3,
+    /// Battlefield 4
+    #[default]
+    Bf4,
+    /// Battlefield Hardline
+    Bfh,
+}
+
+impl GameProfile {
+    /// Selects the profile of the game named by a `version` response.
+    pub fn from_version(version: &Version) -> Option<Self> {
+        Self::from_game_name(&version.game)
+    }
+
+    /// Selects the profile of a game name, such as `BF4`.
+    pub fn from_game_name(name: &str) -> Option<Self> {
+        match name {
+            "BC2" => Some(GameProfile::Bc2),
+            "BF3" => Some(GameProfile::Bf3),
+            "BF4" => Some(GameProfile::Bf4),
+            "BFHL" => Some(GameProfile::Bfh),
+            _ => None,
+        }
+    }
+
+    /// Returns the game name, as found in `version` responses.
+    pub fn game_name(self) -> &'static str {
+        match self {
+            GameProfile::Bc2 => "BC2",
+            GameProfile::Bf3 => "BF3",
+            GameProfile::Bf4 => "BF4",
+            GameProfile::Bfh => "BFHL",
+        }
+    }
+
+    /// Returns whether the game knows the command.
+    pub fn supports(self, command: &str) -> bool {
+        let matches = |entry: &&str| {
+            if entry.ends_with('.') {
+                command.starts_with(entry)
+            } else {
+                command == *entry
+            }
+        };
+        match self {
+            GameProfile::Bc2 => {
+                !BF4_ONLY_COMMANDS.iter().any(matches) && !BC2_MISSING_COMMANDS.iter().any(matches)
+            }
+            GameProfile::Bf3 => !BF4_ONLY_COMMANDS.iter().any(matches),
+            GameProfile::Bf4 | GameProfile::Bfh => true,
+        }
+    }
+
+    /// Returns the duration argument of `admin.yell`, or the game's
+    /// default if none is given.
+    ///
+    /// The duration must precede the players yelled at, so it is always
+    /// sent. BC2 takes it in milliseconds, and later games in seconds.
+    pub fn yell_duration(self, duration: Option<Duration>) -> u64 {
+        match self {
+            GameProfile::Bc2 => {
+                let duration = duration.unwrap_or(BC2_DEFAULT_YELL_DURATION);
+                duration.as_millis() as u64
+            }
+            _ => duration.unwrap_or(DEFAULT_YELL_DURATION).as_secs(),
+        }
+    }
+
+    /// Returns the columns of the game's player info blocks, written
+    /// when encoding one for the game.
+    pub fn player_info_columns(self) -> &'static [&'static str] {
+        match self {
+            GameProfile::Bc2 => BC2_PLAYER_COLUMNS,
+            GameProfile::Bf3 => BF3_PLAYER_COLUMNS,
+            GameProfile::Bf4 | GameProfile::Bfh => BF4_PLAYER_COLUMNS,
+        }
+    }
+
+    /// Returns whether `player.onJoin` carries the player's GUID.
+    pub fn join_has_guid(self) -> bool {
+        self != GameProfile::Bc2
+    }
+}
+
+impl fmt::Display for GameProfile {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.game_name())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_test() {
+        let version = Version {
+            game: "BFHL".to_string(),
+            build: 1,
+        };
+        assert_eq!(GameProfile::from_version(&version), Some(GameProfile::Bfh));
+        assert_eq!(GameProfile::from_game_name("BF1"), None);
+        for profile in &[GameProfile::Bc2, GameProfile::Bf3, GameProfile::Bf4] {
+            assert_eq!(
+                GameProfile::from_game_name(profile.game_name()),
+                Some(*profile)
+            );
+        }
+    }
+
+    #[test]
+    fn supports_test() {
+        assert!(GameProfile::Bf4.supports("currentLevel"));
+        assert!(!GameProfile::Bf3.supports("currentLevel"));
+        assert!(GameProfile::Bf3.supports("squad.leader"));
+        assert!(!GameProfile::Bf3.supports("gameAdmin.list"));
+        assert!(!GameProfile::Bc2.supports("squad.leader"));
+        assert!(GameProfile::Bc2.supports("admin.say"));
+        assert!(GameProfile::Bc2.supports("reservedSlotsList.list"));
+    }
+
+    #[test]
+    fn yell_duration_test() {
+        let duration = Some(Duration::from_secs(3));
+        assert_eq!(GameProfile::Bc2.yell_duration(duration), 3000);
+        assert_eq!(GameProfile::Bc2.yell_duration(None), 5000);
+        assert_eq!(GameProfile::Bf4.yell_duration(duration), 3);
+        assert_eq!(GameProfile::Bf4.yell_duration(None), 10);
+    }
+}
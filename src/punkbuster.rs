@@ -0,0 +1,318 @@
+//! Parsing of PunkBuster server messages.
+//!
+//! Output of `punkBuster.pb_sv_command` arrives asynchronously as the
+//! text of `punkBuster.onMessage` events, one line per event. This
+//! module recognises the common messages, and collects player lists
+//! requested with `pb_sv_plist`.
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+
+const SERVER_PREFIX: &str = "PunkBuster Server: ";
+
+/// A player, as listed by `pb_sv_plist`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PbPlayer {
+    /// PunkBuster slot, starting at 1
+    pub slot: u32,
+    /// PunkBuster GUID, in lowercase hex
+    pub guid: String,
+    /// Address the player connected from
+    pub address: SocketAddr,
+    /// Soldier name
+    pub name: String,
+}
+
+/// A recognised PunkBuster server message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PbMessage {
+    /// The start of a player list.
+    PlayerListStart,
+    /// A player of a player list.
+    PlayerListEntry(PbPlayer),
+    /// The end of a player list, with the number of players listed.
+    PlayerListEnd { count: usize },
+    /// A player was kicked, or also banned.
+    Kick {
+        ban: bool,
+        slot: u32,
+        name: String,
+        reason: String,
+    },
+    /// A screenshot of a player was received.
+    Screenshot {
+        slot: u32,
+        name: String,
+        file: String,
+    },
+    /// Any other server message, without the prefix.
+    Other(String),
+}
+
+/// Parses the text of a `punkBuster.onMessage` event.
+///
+/// Returns `None` if the text is not a PunkBuster server message.
+pub fn parse_message(text: &str) -> Option<PbMessage> {
+    let text = text.trim_end().strip_prefix(SERVER_PREFIX)?;
+    let message = parse_player_list(text)
+        .or_else(|| parse_kick(text))
+        .or_else(|| parse_screenshot(text))
+        .unwrap_or_else(|| PbMessage::Other(text.to_string()));
+    Some(message)
+}
+
+fn parse_player_list(text: &str) -> Option<PbMessage> {
+    if text.starts_with("Player List: ") {
+        return Some(PbMessage::PlayerListStart);
+    }
+    if let Some(rest) = text.strip_prefix("End of Player List (") {
+        let count = rest.split_whitespace().next()?.parse().ok()?;
+        return Some(PbMessage::PlayerListEnd { count });
+    }
+    parse_player(text).map(PbMessage::PlayerListEntry)
+}
+
+/// Parses a line such as
+/// `1  0123abcd...(-) 10.0.0.1:3659 OK   1 3.0 0 (W) "avitex"`.
+fn parse_player(text: &str) -> Option<PbPlayer> {
+    let mut tokens = text.split_whitespace();
+    let slot = tokens.next()?.parse().ok()?;
+    let guid = without_status(tokens.next()?);
+    let address = without_status(tokens.next()?).parse().ok()?;
+    if guid.is_empty() || !guid.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(PbPlayer {
+        slot,
+        guid: guid.to_ascii_lowercase(),
+        address,
+        name: quoted(text)?.to_string(),
+    })
+}
+
+/// Parses a line such as
+/// `Kick/Ban Command Issued (Aimbot) for (slot#3) 10.0.0.1:3659 0123abcd...(-) avitex`.
+fn parse_kick(text: &str) -> Option<PbMessage> {
+    let (ban, rest) = if let Some(rest) = text.strip_prefix("Kick/Ban Command Issued (") {
+        (true, rest)
+    } else {
+        (false, text.strip_prefix("Kick Command Issued (")?)
+    };
+    let (reason, rest) = rest.split_once(") for (slot#")?;
+    let (slot, rest) = rest.split_once(") ")?;
+    // The address and GUID precede the name, which may contain spaces.
+    let name = rest.splitn(3, ' ').nth(2)?;
+    Some(PbMessage::Kick {
+        ban,
+        slot: slot.parse().ok()?,
+        name: name.to_string(),
+        reason: reason.to_string(),
+    })
+}
+
+/// Parses a line such as
+/// `Screenshot C:\pb\svss\pb000123.png successfully received (MD5=...) from 3 avitex [...]`.
+fn parse_screenshot(text: &str) -> Option<PbMessage> {
+    let rest = text.strip_prefix("Screenshot ")?;
+    let (file, rest) = rest.split_once(" successfully received ")?;
+    let (_, rest) = rest.split_once(" from ")?;
+    let (slot, rest) = rest.split_once(" ")?;
+    let name = match rest.rfind(" [") {
+        Some(end) => &rest[..end],
+        None => rest,
+    };
+    Some(PbMessage::Screenshot {
+        slot: slot.parse().ok()?,
+        name: name.to_string(),
+        file: file.to_string(),
+    })
+}
+
+/// Strips a trailing status in parentheses, such as the `(-)` of GUIDs.
+fn without_status(token: &str) -> &str {
+    token.split('(').next().unwrap_or(token)
+}
+
+fn quoted(text: &str) -> Option<&str> {
+    let start = text.find('"')? + 1;
+    let end = text.rfind('"')?;
+    if end < start {
+        return None;
+    }
+    Some(&text[start..end])
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/// Identifies a `pb_sv_plist` request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlistToken(u64);
+
+/// A complete player list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerListing {
+    /// The request the list answers, or `None` if it was not requested
+    /// through the correlator
+    pub token: Option<PlistToken>,
+    pub players: Vec<PbPlayer>,
+    /// Whether the players match the count PunkBuster reported at the end
+    /// of the list, which is not the case when entries were missed
+    pub complete: bool,
+}
+
+/// Correlates player lists with the `pb_sv_plist` requests triggering
+/// them.
+///
+/// PunkBuster answers requests in order, so each list is matched with
+/// the oldest request yet to be answered.
+#[derive(Debug, Default)]
+pub struct PlistCorrelator {
+    next_token: u64,
+    pending: VecDeque<PlistToken>,
+    current: Option<PlayerListing>,
+}
+
+impl PlistCorrelator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a request, returning the token its list will carry.
+    pub fn request(&mut self) -> PlistToken {
+        let token = PlistToken(self.next_token);
+        self.next_token += 1;
+        self.pending.push_back(token);
+        token
+    }
+
+    /// Returns the number of requests yet to be answered.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Feeds a message, returning a player list once complete.
+    pub fn feed(&mut self, message: &PbMessage) -> Option<PlayerListing> {
+        match message {
+            PbMessage::PlayerListStart => {
+                self.current = Some(PlayerListing {
+                    token: self.pending.pop_front(),
+                    players: Vec::new(),
+                    complete: false,
+                });
+                None
+            }
+            PbMessage::PlayerListEntry(player) => {
+                if let Some(listing) = self.current.as_mut() {
+                    listing.players.push(player.clone());
+                }
+                None
+            }
+            PbMessage::PlayerListEnd { count } => {
+                let mut listing = self.current.take()?;
+                listing.complete = listing.players.len() == *count;
+                Some(listing)
+            }
+            _ => None,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUID: &str = "0123456789ABCDEF0123456789abcdef";
+
+    #[test]
+    fn parse_message_test() {
+        assert_eq!(parse_message("Not PunkBuster"), None);
+        assert_eq!(
+            parse_message("PunkBuster Server: Player List: [Slot #] [GUID] [Address] [Status] [Power] [Auth Rate] [Recent SS] [O/S] [Name]\n"),
+            Some(PbMessage::PlayerListStart)
+        );
+        let line = format!(
+            "PunkBuster Server: 3  {}(-) 10.0.0.1:3659 OK   1 3.0 0 (W) \"Some Name\"\n",
+            GUID
+        );
+        assert_eq!(
+            parse_message(&line),
+            Some(PbMessage::PlayerListEntry(PbPlayer {
+                slot: 3,
+                guid: GUID.to_ascii_lowercase(),
+                address: "10.0.0.1:3659".parse().unwrap(),
+                name: "Some Name".to_string(),
+            }))
+        );
+        assert_eq!(
+            parse_message("PunkBuster Server: End of Player List (1 Players)\n"),
+            Some(PbMessage::PlayerListEnd { count: 1 })
+        );
+        let line = format!(
+            "PunkBuster Server: Kick/Ban Command Issued (Aimbot) for (slot#3) 10.0.0.1:3659 {}(-) Some Name\n",
+            GUID
+        );
+        assert_eq!(
+            parse_message(&line),
+            Some(PbMessage::Kick {
+                ban: true,
+                slot: 3,
+                name: "Some Name".to_string(),
+                reason: "Aimbot".to_string(),
+            })
+        );
+        let line = format!(
+            "PunkBuster Server: Screenshot C:\\pb\\svss\\pb000123.png successfully received (MD5=ABC) from 3 avitex [{}(-) 10.0.0.1:3659]\n",
+            GUID
+        );
+        assert_eq!(
+            parse_message(&line),
+            Some(PbMessage::Screenshot {
+                slot: 3,
+                name: "avitex".to_string(),
+                file: "C:\\pb\\svss\\pb000123.png".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_message("PunkBuster Server: Running PB Scheduled Task\n"),
+            Some(PbMessage::Other("Running PB Scheduled Task".to_string()))
+        );
+    }
+
+    #[test]
+    fn correlator_test() {
+        let mut correlator = PlistCorrelator::new();
+        let player = PbPlayer {
+            slot: 1,
+            guid: GUID.to_ascii_lowercase(),
+            address: "10.0.0.1:3659".parse().unwrap(),
+            name: "avitex".to_string(),
+        };
+        let list = |correlator: &mut PlistCorrelator| {
+            assert_eq!(correlator.feed(&PbMessage::PlayerListStart), None);
+            let entry = PbMessage::PlayerListEntry(player.clone());
+            assert_eq!(correlator.feed(&entry), None);
+            correlator.feed(&PbMessage::PlayerListEnd { count: 1 })
+        };
+
+        let first = correlator.request();
+        let second = correlator.request();
+        assert_eq!(correlator.pending(), 2);
+        let listing = list(&mut correlator).unwrap();
+        assert_eq!(listing.token, Some(first));
+        assert_eq!(listing.players, std::slice::from_ref(&player));
+        assert!(listing.complete);
+        assert_eq!(list(&mut correlator).unwrap().token, Some(second));
+        // Lists requested elsewhere, such as by an in-game admin.
+        assert_eq!(list(&mut correlator).unwrap().token, None);
+        assert_eq!(
+            correlator.feed(&PbMessage::PlayerListEnd { count: 0 }),
+            None
+        );
+        // An entry was missed.
+        assert_eq!(correlator.feed(&PbMessage::PlayerListStart), None);
+        let listing = correlator.feed(&PbMessage::PlayerListEnd { count: 1 });
+        assert!(!listing.unwrap().complete);
+    }
+}
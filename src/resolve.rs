@@ -0,0 +1,237 @@
+//! Resolution of partial or misspelled player names.
+//!
+//! Admins refer to players by whatever they manage to type, whereas
+//! commands need exact names. Names are matched case-insensitively,
+//! from the most to the least confident kind of match.
+
+use std::cmp::Ordering;
+
+use crate::types::{PlayerList, PlayerName};
+
+/// How a name matched the query, from the most confident kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MatchKind {
+    /// The name equals the query.
+    Exact,
+    /// The name equals the query, ignoring case.
+    ExactIgnoreCase,
+    /// The name starts with the query.
+    Prefix,
+    /// The name contains the query.
+    Substring,
+    /// The name, or its start, is within a few edits of the query.
+    Fuzzy,
+}
+
+/// A name matching a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameMatch<'a> {
+    pub name: &'a PlayerName,
+    pub kind: MatchKind,
+    /// From 0 to 1, higher being more confident
+    pub confidence: f32,
+}
+
+/// The outcome of resolving a query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution<'a> {
+    /// A single name matched best.
+    Found(NameMatch<'a>),
+    /// Several names matched equally well, most confident first.
+    Ambiguous(Vec<NameMatch<'a>>),
+    NotFound,
+}
+
+impl<'a> Resolution<'a> {
+    /// Returns the name found, if any.
+    pub fn found(&self) -> Option<&'a PlayerName> {
+        match self {
+            Resolution::Found(m) => Some(m.name),
+            _ => None,
+        }
+    }
+}
+
+/// Returns every name matching the query, most confident first.
+pub fn rank<'a, I>(query: &str, names: I) -> Vec<NameMatch<'a>>
+where
+    I: IntoIterator<Item = &'a PlayerName>,
+{
+    let query_lower = query.to_ascii_lowercase();
+    let mut matches: Vec<NameMatch<'a>> = names
+        .into_iter()
+        .filter_map(|name| match_name(query, &query_lower, name))
+        .collect();
+    matches.sort_by(|a, b| {
+        a.kind
+            .cmp(&b.kind)
+            .then(
+                b.confidence
+                    .partial_cmp(&a.confidence)
+                    .unwrap_or(Ordering::Equal),
+            )
+            .then_with(|| a.name.as_str().cmp(b.name.as_str()))
+    });
+    matches
+}
+
+/// Resolves a query to a single name.
+///
+/// Only the most confident kind of match is considered, so a query
+/// matching one name exactly and others as a prefix is not ambiguous.
+/// Fuzzy matches are only ambiguous if equally close to the query.
+pub fn resolve<'a, I>(query: &str, names: I) -> Resolution<'a>
+where
+    I: IntoIterator<Item = &'a PlayerName>,
+{
+    let mut matches = rank(query, names);
+    let best = match matches.first() {
+        Some(best) => (best.kind, best.confidence),
+        None => return Resolution::NotFound,
+    };
+    matches.retain(|m| m.kind == best.0 && (m.kind != MatchKind::Fuzzy || m.confidence >= best.1));
+    if matches.len() == 1 {
+        Resolution::Found(matches.remove(0))
+    } else {
+        Resolution::Ambiguous(matches)
+    }
+}
+
+/// Resolves a query against the players of a list.
+pub fn resolve_player<'a>(query: &str, list: &'a PlayerList) -> Resolution<'a> {
+    resolve(query, list.players.iter().map(|p| &p.name))
+}
+
+fn match_name<'a>(query: &str, query_lower: &str, name: &'a PlayerName) -> Option<NameMatch<'a>> {
+    if query.is_empty() {
+        return None;
+    }
+    let name_str = name.as_str();
+    let name_lower = name_str.to_ascii_lowercase();
+    // The share of the name covered by the query.
+    let coverage = query.len() as f32 / name_str.len().max(query.len()) as f32;
+    let (kind, confidence) = if name_str == query {
+        (MatchKind::Exact, 1.0)
+    } else if name_lower == query_lower {
+        (MatchKind::ExactIgnoreCase, 0.95)
+    } else if name_lower.starts_with(query_lower) {
+        (MatchKind::Prefix, 0.6 + 0.3 * coverage)
+    } else if name_lower.contains(query_lower) {
+        (MatchKind::Substring, 0.4 + 0.3 * coverage)
+    } else {
+        // Compare with the whole name, and with its start as far as the
+        // query goes, counting the characters left out as edits.
+        let len = name_str.len().max(query.len());
+        let max = max_distance(query.len());
+        let whole = Some(edit_distance(query_lower, &name_lower))
+            .filter(|&distance| distance <= max)
+            .map(|distance| len - distance);
+        let prefix = name_lower
+            .get(..query.len())
+            .map(|prefix| edit_distance(query_lower, prefix))
+            .filter(|&distance| distance <= max)
+            .map(|distance| query.len() - distance);
+        let matched = whole.max(prefix)?;
+        (MatchKind::Fuzzy, 0.4 * matched as f32 / len as f32)
+    };
+    Some(NameMatch {
+        name,
+        kind,
+        confidence,
+    })
+}
+
+/// Allows one edit for every three characters typed, and at least one.
+fn max_distance(query_len: usize) -> usize {
+    (query_len / 3).max(1)
+}
+
+/// Returns the Levenshtein distance between two ASCII strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + if ca == cb { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<PlayerName> {
+        names.iter().map(|n| PlayerName::new(n).unwrap()).collect()
+    }
+
+    fn found<'a>(resolution: Resolution<'a>) -> (&'a str, MatchKind) {
+        match resolution {
+            Resolution::Found(m) => (m.name.as_str(), m.kind),
+            other => panic!("expected a single match, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn edit_distance_test() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+    }
+
+    #[test]
+    fn resolve_test() {
+        let roster = names(&["Sn1per", "sniperwolf", "avitex", "Avi", "xXSlayerXx"]);
+
+        assert_eq!(found(resolve("Avi", &roster)), ("Avi", MatchKind::Exact));
+        assert_eq!(
+            found(resolve("AVITEX", &roster)),
+            ("avitex", MatchKind::ExactIgnoreCase)
+        );
+        assert_eq!(
+            found(resolve("sn1p", &roster)),
+            ("Sn1per", MatchKind::Prefix)
+        );
+        assert_eq!(
+            found(resolve("slayer", &roster)),
+            ("xXSlayerXx", MatchKind::Substring)
+        );
+        assert_eq!(
+            found(resolve("avitx", &roster)),
+            ("avitex", MatchKind::Fuzzy)
+        );
+        assert_eq!(
+            found(resolve("snlp", &roster)),
+            ("Sn1per", MatchKind::Fuzzy)
+        );
+        assert_eq!(resolve("nobody", &roster), Resolution::NotFound);
+        assert_eq!(resolve("", &roster), Resolution::NotFound);
+
+        match resolve("s", &roster) {
+            Resolution::Ambiguous(matches) => {
+                let names: Vec<&str> = matches.iter().map(|m| m.name.as_str()).collect();
+                assert_eq!(names, ["Sn1per", "sniperwolf"]);
+            }
+            other => panic!("expected ambiguity, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rank_test() {
+        let roster = names(&["avitex", "xavi", "avi"]);
+        let ranked = rank("avi", &roster);
+        let kinds: Vec<MatchKind> = ranked.iter().map(|m| m.kind).collect();
+        assert_eq!(
+            kinds,
+            [MatchKind::Exact, MatchKind::Prefix, MatchKind::Substring]
+        );
+        assert!(ranked.windows(2).all(|w| w[0].confidence > w[1].confidence));
+    }
+}
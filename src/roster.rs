@@ -0,0 +1,437 @@
+//! Tracking of the players on a server.
+//!
+//! A [`Roster`] is fed the server's events as they arrive, and the
+//! result of `admin.listPlayers` every now and then. Events keep it
+//! current between listings, which in turn correct any missed event
+//! and refresh the scores and pings events do not carry.
+//!
+//! The roster does not listen to the connection itself, so the caller
+//! must apply every event in the order received, such as from a
+//! subscription to the connection's `client::EventHandler`, and act on
+//! the changes returned. A `client::RosterDriver` does all of this,
+//! sending the changes to its subscribers.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::event::ServerEvent;
+use crate::types::{PlayerGuid, PlayerList, PlayerName, SquadId, TeamId};
+
+/// A player on the server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RosterPlayer {
+    pub name: PlayerName,
+    /// Player's GUID, once known
+    pub guid: Option<PlayerGuid>,
+    pub team_id: TeamId,
+    pub squad_id: SquadId,
+    pub kills: u32,
+    pub deaths: u32,
+    pub score: u32,
+    /// Ping as of the last listing, if the game reports it
+    pub ping: Option<u32>,
+    /// When the player joined, or `None` if they were already on the
+    /// server when tracking started
+    pub joined_at: Option<SystemTime>,
+}
+
+impl RosterPlayer {
+    fn new(name: PlayerName, joined_at: Option<SystemTime>) -> Self {
+        Self {
+            name,
+            guid: None,
+            team_id: TeamId::NEUTRAL,
+            squad_id: SquadId::NONE,
+            kills: 0,
+            deaths: 0,
+            score: 0,
+            ping: None,
+            joined_at,
+        }
+    }
+}
+
+/// The players on the server, by name.
+pub type RosterSnapshot = Arc<HashMap<PlayerName, RosterPlayer>>;
+
+/// A change to the roster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RosterChange {
+    Joined(PlayerName),
+    Left(PlayerName),
+    /// A player moved to another team, and possibly squad.
+    TeamChanged {
+        name: PlayerName,
+        team_id: TeamId,
+        squad_id: SquadId,
+    },
+    /// A player moved to another squad of their team.
+    SquadChanged {
+        name: PlayerName,
+        squad_id: SquadId,
+    },
+    /// The kills, deaths, score, ping or GUID of a player changed.
+    Updated(PlayerName),
+}
+
+/// The players on a server, kept current from events and listings.
+///
+/// Players are shared with snapshots until modified, so taking a
+/// snapshot is cheap.
+#[derive(Debug, Clone, Default)]
+pub struct Roster {
+    players: RosterSnapshot,
+    synced: bool,
+}
+
+impl Roster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a player, if on the server.
+    pub fn get(&self, name: &PlayerName) -> Option<&RosterPlayer> {
+        self.players.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Returns the players, as of now.
+    pub fn snapshot(&self) -> RosterSnapshot {
+        self.players.clone()
+    }
+
+    /// Applies an event, returning the resulting changes.
+    pub fn apply_event(&mut self, event: &ServerEvent, now: SystemTime) -> Vec<RosterChange> {
+        let mut changes = Vec::new();
+        match event {
+            ServerEvent::PlayerAuthenticated { name } => {
+                self.entry(name, now, &mut changes);
+            }
+            ServerEvent::PlayerJoin { name, guid } => {
+                let player = self.entry(name, now, &mut changes);
+                if guid.is_some() && player.guid != *guid {
+                    player.guid = guid.clone();
+                    push_update(&mut changes, name);
+                }
+            }
+            ServerEvent::PlayerLeave { name, .. } => {
+                let player = Arc::make_mut(&mut self.players).remove(name);
+                changes.extend(player.map(|player| RosterChange::Left(player.name)));
+            }
+            ServerEvent::PlayerSpawn { name, team_id } => {
+                let squad_id = self.entry(name, now, &mut changes).squad_id;
+                self.move_player(name, *team_id, squad_id, &mut changes);
+            }
+            ServerEvent::PlayerTeamChange {
+                name,
+                team_id,
+                squad_id,
+            }
+            | ServerEvent::PlayerSquadChange {
+                name,
+                team_id,
+                squad_id,
+            } => {
+                self.entry(name, now, &mut changes);
+                self.move_player(name, *team_id, *squad_id, &mut changes);
+            }
+            ServerEvent::PlayerKill { killer, victim, .. } => {
+                self.entry(victim, now, &mut changes).deaths += 1;
+                push_update(&mut changes, victim);
+                match killer {
+                    Some(killer) if killer != victim => {
+                        self.entry(killer, now, &mut changes).kills += 1;
+                        push_update(&mut changes, killer);
+                    }
+                    _ => {}
+                }
+            }
+            ServerEvent::LevelLoaded { .. } => {
+                // The scoreboard is reset with each round.
+                for player in Arc::make_mut(&mut self.players).values_mut() {
+                    if player.kills != 0 || player.deaths != 0 || player.score != 0 {
+                        player.kills = 0;
+                        player.deaths = 0;
+                        player.score = 0;
+                        changes.push(RosterChange::Updated(player.name.clone()));
+                    }
+                }
+            }
+            _ => {}
+        }
+        changes
+    }
+
+    /// Reconciles with a listing of all players, requested at the given
+    /// time, returning the changes the listing revealed.
+    ///
+    /// Players missing from the listing are considered gone, unless they
+    /// joined after the listing was requested. Players listed before any
+    /// join event are considered to have been on the server when
+    /// tracking started, if this is the first listing.
+    pub fn reconcile(&mut self, list: &PlayerList, requested_at: SystemTime) -> Vec<RosterChange> {
+        let mut changes = Vec::new();
+        let joined_at = if self.synced {
+            Some(requested_at)
+        } else {
+            None
+        };
+        self.synced = true;
+
+        let players = Arc::make_mut(&mut self.players);
+        let listed: HashSet<&PlayerName> = list.players.iter().map(|info| &info.name).collect();
+        let mut gone: Vec<PlayerName> = players
+            .values()
+            .filter(|player| !listed.contains(&player.name))
+            .filter(|player| player.joined_at.is_none_or(|at| at <= requested_at))
+            .map(|player| player.name.clone())
+            .collect();
+        gone.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        for name in gone {
+            players.remove(&name);
+            changes.push(RosterChange::Left(name));
+        }
+
+        for info in &list.players {
+            let player = match players.get_mut(&info.name) {
+                Some(player) => player,
+                None => {
+                    let mut player = RosterPlayer::new(info.name.clone(), joined_at);
+                    player.guid = info.guid.clone();
+                    player.team_id = info.team_id;
+                    player.squad_id = info.squad_id;
+                    player.kills = info.kills;
+                    player.deaths = info.deaths;
+                    player.score = info.score;
+                    player.ping = info.ping;
+                    players.insert(info.name.clone(), player);
+                    changes.push(RosterChange::Joined(info.name.clone()));
+                    continue;
+                }
+            };
+            if player.team_id != info.team_id {
+                changes.push(RosterChange::TeamChanged {
+                    name: info.name.clone(),
+                    team_id: info.team_id,
+                    squad_id: info.squad_id,
+                });
+            } else if player.squad_id != info.squad_id {
+                changes.push(RosterChange::SquadChanged {
+                    name: info.name.clone(),
+                    squad_id: info.squad_id,
+                });
+            }
+            player.team_id = info.team_id;
+            player.squad_id = info.squad_id;
+
+            let guid = info.guid.clone().or_else(|| player.guid.clone());
+            let ping = info.ping.or(player.ping);
+            if (player.kills, player.deaths, player.score, player.ping)
+                != (info.kills, info.deaths, info.score, ping)
+                || player.guid != guid
+            {
+                player.kills = info.kills;
+                player.deaths = info.deaths;
+                player.score = info.score;
+                player.ping = ping;
+                player.guid = guid;
+                push_update(&mut changes, &info.name);
+            }
+        }
+        changes
+    }
+
+    /// Returns a player, adding them if not yet on the roster.
+    fn entry(
+        &mut self,
+        name: &PlayerName,
+        now: SystemTime,
+        changes: &mut Vec<RosterChange>,
+    ) -> &mut RosterPlayer {
+        Arc::make_mut(&mut self.players)
+            .entry(name.clone())
+            .or_insert_with(|| {
+                changes.push(RosterChange::Joined(name.clone()));
+                RosterPlayer::new(name.clone(), Some(now))
+            })
+    }
+
+    fn move_player(
+        &mut self,
+        name: &PlayerName,
+        team_id: TeamId,
+        squad_id: SquadId,
+        changes: &mut Vec<RosterChange>,
+    ) {
+        let player = match Arc::make_mut(&mut self.players).get_mut(name) {
+            Some(player) => player,
+            None => return,
+        };
+        if player.team_id != team_id {
+            changes.push(RosterChange::TeamChanged {
+                name: name.clone(),
+                team_id,
+                squad_id,
+            });
+        } else if player.squad_id != squad_id {
+            changes.push(RosterChange::SquadChanged {
+                name: name.clone(),
+                squad_id,
+            });
+        }
+        player.team_id = team_id;
+        player.squad_id = squad_id;
+    }
+}
+
+/// Records an update, unless the player just joined.
+fn push_update(changes: &mut Vec<RosterChange>, name: &PlayerName) {
+    let joined = changes
+        .iter()
+        .any(|change| *change == RosterChange::Joined(name.clone()));
+    if !joined {
+        changes.push(RosterChange::Updated(name.clone()));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::types::PlayerInfo;
+
+    fn name(s: &str) -> PlayerName {
+        PlayerName::new(s).unwrap()
+    }
+
+    fn info(s: &str, team: u32, squad: u32, kills: u32) -> PlayerInfo {
+        PlayerInfo {
+            name: name(s),
+            guid: None,
+            team_id: TeamId::new(team).unwrap(),
+            squad_id: SquadId::new(squad).unwrap(),
+            kills,
+            deaths: 0,
+            score: kills * 100,
+            rank: None,
+            ping: Some(50),
+            player_type: None,
+            extras: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn events_test() {
+        let now = SystemTime::UNIX_EPOCH;
+        let mut roster = Roster::new();
+        let join = ServerEvent::PlayerJoin {
+            name: name("avitex"),
+            guid: None,
+        };
+        assert_eq!(
+            roster.apply_event(&join, now),
+            [RosterChange::Joined(name("avitex"))]
+        );
+        assert_eq!(roster.apply_event(&join, now), []);
+        assert_eq!(roster.get(&name("avitex")).unwrap().joined_at, Some(now));
+
+        let team = TeamId::new(1).unwrap();
+        let spawn = ServerEvent::PlayerSpawn {
+            name: name("avitex"),
+            team_id: team,
+        };
+        assert_eq!(
+            roster.apply_event(&spawn, now),
+            [RosterChange::TeamChanged {
+                name: name("avitex"),
+                team_id: team,
+                squad_id: SquadId::NONE,
+            }]
+        );
+
+        let snapshot = roster.snapshot();
+        let kill = ServerEvent::PlayerKill {
+            killer: Some(name("avitex")),
+            victim: name("other"),
+            weapon: "M16A4".to_string(),
+            headshot: false,
+        };
+        assert_eq!(
+            roster.apply_event(&kill, now),
+            [
+                RosterChange::Joined(name("other")),
+                RosterChange::Updated(name("avitex")),
+            ]
+        );
+        assert_eq!(roster.get(&name("avitex")).unwrap().kills, 1);
+        assert_eq!(roster.get(&name("other")).unwrap().deaths, 1);
+        // Snapshots are not affected by later changes.
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[&name("avitex")].kills, 0);
+
+        let leave = ServerEvent::PlayerLeave {
+            name: name("other"),
+            info: PlayerList::default(),
+        };
+        assert_eq!(
+            roster.apply_event(&leave, now),
+            [RosterChange::Left(name("other"))]
+        );
+        assert_eq!(roster.len(), 1);
+    }
+
+    #[test]
+    fn reconcile_test() {
+        let start = SystemTime::UNIX_EPOCH;
+        let later = start + Duration::from_secs(60);
+        let mut roster = Roster::new();
+        let list = PlayerList {
+            players: vec![info("avitex", 1, 1, 0), info("other", 2, 0, 0)],
+        };
+        let changes = roster.reconcile(&list, start);
+        assert!(changes.contains(&RosterChange::Joined(name("avitex"))));
+        assert_eq!(roster.get(&name("avitex")).unwrap().joined_at, None);
+        assert_eq!(roster.get(&name("other")).unwrap().ping, Some(50));
+        assert_eq!(roster.reconcile(&list, start), []);
+
+        let list = PlayerList {
+            players: vec![info("avitex", 1, 2, 3), info("late", 2, 0, 0)],
+        };
+        assert_eq!(
+            roster.reconcile(&list, later),
+            [
+                RosterChange::Left(name("other")),
+                RosterChange::SquadChanged {
+                    name: name("avitex"),
+                    squad_id: SquadId::new(2).unwrap(),
+                },
+                RosterChange::Updated(name("avitex")),
+                RosterChange::Joined(name("late")),
+            ]
+        );
+        assert_eq!(roster.get(&name("avitex")).unwrap().score, 300);
+        assert_eq!(roster.get(&name("late")).unwrap().joined_at, Some(later));
+
+        // A player joining while the listing is under way is not gone.
+        let join = ServerEvent::PlayerJoin {
+            name: name("newcomer"),
+            guid: None,
+        };
+        let listed_at = later + Duration::from_secs(60);
+        roster.apply_event(&join, listed_at + Duration::from_millis(10));
+        assert_eq!(roster.reconcile(&list, listed_at), []);
+        assert!(roster.get(&name("newcomer")).is_some());
+    }
+}
@@ -0,0 +1,438 @@
+//! Tracking of rounds, and of the maps they are played on.
+//!
+//! A [`RoundTracker`] is fed the server's level and round events, and
+//! the result of `serverInfo` every now and then. Events drive the
+//! lifecycle of rounds, whereas polls sample the tickets of teams and
+//! catch up with any missed event.
+//!
+//! The tracker does not listen to the connection itself. Events can be
+//! received by subscribing to the `client::EventHandler` of the
+//! connection, and applied in the order received, or left to a
+//! `client::RoundDriver` along with the polls.
+
+use std::time::{Duration, SystemTime};
+
+use crate::event::ServerEvent;
+use crate::types::{ServerInfo, TeamId, TeamScores};
+
+/// The scores of the teams at some point of a round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TicketSample {
+    pub at: SystemTime,
+    pub score: Vec<u32>,
+}
+
+/// A round, current or past.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Round {
+    pub map: String,
+    /// Game mode, unknown on BC2 until polled
+    pub game_mode: Option<String>,
+    /// Rounds played on the map before this one
+    pub rounds_played: u32,
+    pub rounds_total: u32,
+    /// When the round started, if tracking started before, or the
+    /// server reported the round time
+    pub started_at: Option<SystemTime>,
+    pub ended_at: Option<SystemTime>,
+    /// Scores of the teams, whenever they changed
+    pub tickets: Vec<TicketSample>,
+    pub winner: Option<TeamId>,
+    pub final_scores: Option<TeamScores>,
+}
+
+impl Round {
+    fn from_server_info(info: &ServerInfo, now: SystemTime) -> Self {
+        let started_at = info
+            .round_time
+            .and_then(|secs| now.checked_sub(Duration::from_secs(secs.into())));
+        Self {
+            map: info.map.clone(),
+            game_mode: Some(info.game_mode.clone()),
+            rounds_played: info.rounds_played,
+            rounds_total: info.rounds_total,
+            started_at,
+            ended_at: None,
+            tickets: Vec::new(),
+            winner: None,
+            final_scores: None,
+        }
+    }
+
+    fn sample(&mut self, score: &[u32], at: SystemTime) {
+        if self.tickets.last().map(|last| &last.score[..]) != Some(score) {
+            self.tickets.push(TicketSample {
+                at,
+                score: score.to_vec(),
+            });
+        }
+    }
+}
+
+/// A change in the lifecycle of rounds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoundEvent {
+    /// A level of another map or game mode was loaded.
+    MapChanged {
+        map: String,
+        game_mode: Option<String>,
+    },
+    RoundStarted {
+        map: String,
+        game_mode: Option<String>,
+        rounds_played: u32,
+        rounds_total: u32,
+    },
+    /// A round was won. The scores are the last polled if the server
+    /// did not send the final ones.
+    RoundEnded {
+        winner: TeamId,
+        scores: TeamScores,
+        started_at: Option<SystemTime>,
+        ended_at: SystemTime,
+    },
+}
+
+/// Tracks the current round from events and polls.
+///
+/// The round in progress when tracking starts is picked up by the first
+/// poll, without emitting events. Rounds cut short by loading another
+/// level, such as with `mapList.runNextRound`, do not emit
+/// [`RoundEvent::RoundEnded`].
+#[derive(Debug, Clone, Default)]
+pub struct RoundTracker {
+    round: Option<Round>,
+    end_reported: bool,
+    /// Whether a poll started the current round before its level load
+    /// event arrived
+    load_pending: bool,
+}
+
+impl RoundTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current round, or the last one between rounds.
+    pub fn round(&self) -> Option<&Round> {
+        self.round.as_ref()
+    }
+
+    /// Returns whether the current round is over.
+    pub fn is_over(&self) -> bool {
+        self.round.as_ref().and_then(|r| r.ended_at).is_some()
+    }
+
+    /// Applies an event, returning the resulting lifecycle events.
+    pub fn apply_event(&mut self, event: &ServerEvent, now: SystemTime) -> Vec<RoundEvent> {
+        let mut events = Vec::new();
+        match event {
+            ServerEvent::LevelLoaded {
+                level,
+                game_mode,
+                rounds_played,
+                rounds_total,
+            } => {
+                if self.take_pending_load(level, game_mode.as_ref(), *rounds_played) {
+                    return events;
+                }
+                self.start(
+                    Round {
+                        map: level.clone(),
+                        game_mode: game_mode.clone(),
+                        rounds_played: *rounds_played,
+                        rounds_total: *rounds_total,
+                        started_at: Some(now),
+                        ended_at: None,
+                        tickets: Vec::new(),
+                        winner: None,
+                        final_scores: None,
+                    },
+                    now,
+                    &mut events,
+                );
+            }
+            ServerEvent::RoundOver { winning_team } => {
+                if let Some(round) = self.round.as_mut() {
+                    round.winner = Some(*winning_team);
+                    round.ended_at.get_or_insert(now);
+                    self.report_end(false, &mut events);
+                }
+            }
+            ServerEvent::RoundOverTeamScores { scores } => {
+                if let Some(round) = self.round.as_mut() {
+                    round.sample(&scores.score, now);
+                    round.final_scores = Some(scores.clone());
+                    round.ended_at.get_or_insert(now);
+                    self.report_end(false, &mut events);
+                }
+            }
+            _ => {}
+        }
+        events
+    }
+
+    /// Applies the result of a `serverInfo` poll, returning the
+    /// lifecycle events of any missed level load.
+    pub fn apply_server_info(&mut self, info: &ServerInfo, now: SystemTime) -> Vec<RoundEvent> {
+        let mut events = Vec::new();
+        let round = match self.round.as_mut() {
+            Some(round) => round,
+            None => {
+                let mut round = Round::from_server_info(info, now);
+                round.sample(&info.scores.score, now);
+                self.round = Some(round);
+                return events;
+            }
+        };
+        let same_mode = match &round.game_mode {
+            Some(mode) => *mode == info.game_mode,
+            None => true,
+        };
+        if round.map != info.map || !same_mode || round.rounds_played != info.rounds_played {
+            let mut next = Round::from_server_info(info, now);
+            next.sample(&info.scores.score, now);
+            self.start(next, now, &mut events);
+            self.load_pending = true;
+        } else {
+            round.game_mode = Some(info.game_mode.clone());
+            if round.ended_at.is_none() {
+                round.sample(&info.scores.score, now);
+            }
+        }
+        events
+    }
+
+    /// Returns whether a level load is the one a poll already started
+    /// the current round for.
+    fn take_pending_load(
+        &mut self,
+        map: &str,
+        game_mode: Option<&String>,
+        rounds_played: u32,
+    ) -> bool {
+        let pending = std::mem::replace(&mut self.load_pending, false);
+        let round = match self.round.as_mut() {
+            Some(round) if pending => round,
+            _ => return false,
+        };
+        let same_mode = match (&round.game_mode, game_mode) {
+            (Some(mode), Some(loaded)) => mode == loaded,
+            _ => true,
+        };
+        if round.map != map || !same_mode || round.rounds_played != rounds_played {
+            return false;
+        }
+        if round.game_mode.is_none() {
+            round.game_mode = game_mode.cloned();
+        }
+        true
+    }
+
+    /// Ends the current round if needed, and starts another.
+    fn start(&mut self, mut next: Round, now: SystemTime, events: &mut Vec<RoundEvent>) {
+        self.report_end(true, events);
+        let map_changed = match &self.round {
+            Some(round) => {
+                round.map != next.map
+                    || (round.game_mode.is_some()
+                        && next.game_mode.is_some()
+                        && round.game_mode != next.game_mode)
+            }
+            None => true,
+        };
+        if map_changed {
+            events.push(RoundEvent::MapChanged {
+                map: next.map.clone(),
+                game_mode: next.game_mode.clone(),
+            });
+        }
+        events.push(RoundEvent::RoundStarted {
+            map: next.map.clone(),
+            game_mode: next.game_mode.clone(),
+            rounds_played: next.rounds_played,
+            rounds_total: next.rounds_total,
+        });
+        next.started_at = next.started_at.or(Some(now));
+        self.round = Some(next);
+        self.end_reported = false;
+        self.load_pending = false;
+    }
+
+    /// Reports the end of the current round once both its winner and
+    /// final scores are known, or as soon as the winner is known if
+    /// the next round is starting.
+    fn report_end(&mut self, starting: bool, events: &mut Vec<RoundEvent>) {
+        let round = match self.round.as_ref() {
+            Some(round) if !self.end_reported => round,
+            _ => return,
+        };
+        let (winner, ended_at) = match (round.winner, round.ended_at) {
+            (Some(winner), Some(ended_at)) => (winner, ended_at),
+            _ => return,
+        };
+        let scores = match &round.final_scores {
+            Some(scores) => scores.clone(),
+            None if starting => TeamScores {
+                score: round
+                    .tickets
+                    .last()
+                    .map(|sample| sample.score.clone())
+                    .unwrap_or_default(),
+                target_score: 0,
+            },
+            None => return,
+        };
+        events.push(RoundEvent::RoundEnded {
+            winner,
+            scores,
+            started_at: round.started_at,
+            ended_at,
+        });
+        self.end_reported = true;
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn level(map: &str, rounds_played: u32) -> ServerEvent {
+        ServerEvent::LevelLoaded {
+            level: map.to_string(),
+            game_mode: Some("ConquestLarge0".to_string()),
+            rounds_played,
+            rounds_total: 2,
+        }
+    }
+
+    fn server_info(map: &str, rounds_played: u32, score: Vec<u32>) -> ServerInfo {
+        ServerInfo {
+            server_name: "Test".to_string(),
+            player_count: 0,
+            max_player_count: 64,
+            game_mode: "ConquestLarge0".to_string(),
+            map: map.to_string(),
+            rounds_played,
+            rounds_total: 2,
+            scores: TeamScores {
+                score,
+                target_score: 0,
+            },
+            online_state: None,
+            ranked: None,
+            punkbuster: None,
+            has_game_password: None,
+            server_uptime: None,
+            round_time: Some(30),
+            game_address: None,
+            punkbuster_version: None,
+            join_queue_enabled: None,
+            region: None,
+            closest_ping_site: None,
+            country: None,
+            matchmaking_enabled: None,
+            blaze_player_count: None,
+            blaze_game_state: None,
+            extra: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn lifecycle_test() {
+        let mut tracker = RoundTracker::new();
+        let team = TeamId::new(1).unwrap();
+        assert_eq!(
+            tracker.apply_event(&level("MP_Prison", 0), at(0)),
+            [
+                RoundEvent::MapChanged {
+                    map: "MP_Prison".to_string(),
+                    game_mode: Some("ConquestLarge0".to_string()),
+                },
+                RoundEvent::RoundStarted {
+                    map: "MP_Prison".to_string(),
+                    game_mode: Some("ConquestLarge0".to_string()),
+                    rounds_played: 0,
+                    rounds_total: 2,
+                },
+            ]
+        );
+        let info = server_info("MP_Prison", 0, vec![800, 800]);
+        assert_eq!(tracker.apply_server_info(&info, at(10)), []);
+        assert_eq!(tracker.apply_server_info(&info, at(20)), []);
+        let info = server_info("MP_Prison", 0, vec![700, 650]);
+        assert_eq!(tracker.apply_server_info(&info, at(30)), []);
+        assert_eq!(tracker.round().unwrap().tickets.len(), 2);
+
+        let scores = TeamScores {
+            score: vec![0, 120],
+            target_score: 0,
+        };
+        let over = ServerEvent::RoundOver {
+            winning_team: TeamId::new(2).unwrap(),
+        };
+        assert_eq!(tracker.apply_event(&over, at(40)), []);
+        assert!(tracker.is_over());
+        let over = ServerEvent::RoundOverTeamScores {
+            scores: scores.clone(),
+        };
+        assert_eq!(
+            tracker.apply_event(&over, at(41)),
+            [RoundEvent::RoundEnded {
+                winner: TeamId::new(2).unwrap(),
+                scores,
+                started_at: Some(at(0)),
+                ended_at: at(40),
+            }]
+        );
+
+        // Another round on the same map.
+        let events = tracker.apply_event(&level("MP_Prison", 1), at(60));
+        assert_eq!(events.len(), 1);
+        // The winner alone suffices once the next round starts.
+        tracker.apply_event(&ServerEvent::RoundOver { winning_team: team }, at(90));
+        let events = tracker.apply_event(&level("MP_Siege", 0), at(100));
+        assert_eq!(events.len(), 3);
+        match &events[0] {
+            RoundEvent::RoundEnded { winner, scores, .. } => {
+                assert_eq!(*winner, team);
+                assert!(scores.score.is_empty());
+            }
+            other => panic!("expected the end of the round, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn poll_test() {
+        let mut tracker = RoundTracker::new();
+        let info = server_info("MP_Prison", 0, vec![800, 800]);
+        // The round in progress is picked up silently.
+        assert_eq!(tracker.apply_server_info(&info, at(100)), []);
+        assert_eq!(tracker.round().unwrap().started_at, Some(at(70)));
+
+        // A level load was missed.
+        let info = server_info("MP_Siege", 0, vec![800, 800]);
+        let events = tracker.apply_server_info(&info, at(200));
+        assert_eq!(
+            events[0],
+            RoundEvent::MapChanged {
+                map: "MP_Siege".to_string(),
+                game_mode: Some("ConquestLarge0".to_string()),
+            }
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!(tracker.round().unwrap().map, "MP_Siege");
+
+        // The level load the poll caught up with arrives late.
+        assert_eq!(tracker.apply_event(&level("MP_Siege", 0), at(201)), []);
+        assert_eq!(tracker.round().unwrap().started_at, Some(at(170)));
+        // Later loads of the same level, such as a restart, do start rounds.
+        assert_eq!(tracker.apply_event(&level("MP_Siege", 0), at(300)).len(), 1);
+    }
+}
@@ -0,0 +1,121 @@
+use super::{PlayerId, Timeout};
+use crate::conn::{word_from_string, BodyError, FromWords, ToWords, Word, WordReader};
+
+/// An entry of the server's ban list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BanEntry {
+    /// The banned player
+    pub id: PlayerId,
+    /// How long the ban lasts, with the time or rounds left
+    pub timeout: Timeout,
+    /// Reason shown to the player, possibly empty
+    pub reason: String,
+}
+
+/// Reads the id-type and id, the ban-type, seconds and rounds left,
+/// and the reason.
+impl FromWords for BanEntry {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        Ok(Self {
+            id: reader.decode()?,
+            timeout: Timeout::from_ban_list_words(reader)?,
+            reason: reader.parse()?,
+        })
+    }
+}
+
+/// # Panics
+///
+/// Panics if the reason contains characters not valid in a word.
+impl ToWords for BanEntry {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        self.id.to_words(words);
+        self.timeout.to_ban_list_words(words);
+        words.push(word_from_string(self.reason.clone()));
+    }
+}
+
+/// A page of ban list entries, as returned by `banList.list`.
+///
+/// Entries follow one another without a count prefix, so all
+/// remaining words are read as entries.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BanList {
+    pub entries: Vec<BanEntry>,
+}
+
+impl FromWords for BanList {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let mut entries = Vec::new();
+        while !reader.is_empty() {
+            entries.push(reader.decode()?);
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl ToWords for BanList {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        for entry in &self.entries {
+            entry.to_words(words);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conn::Body;
+    use crate::types::PlayerName;
+
+    #[test]
+    fn ban_list_test() {
+        let body = Body::new(vec![
+            "OK",
+            "name",
+            "cheater",
+            "perm",
+            "0",
+            "0",
+            "Aimbot",
+            "ip",
+            "10.0.0.1",
+            "seconds",
+            "3540",
+            "0",
+            "",
+            "name",
+            "griefer",
+            "rounds",
+            "0",
+            "2",
+            "Teamkilling",
+        ])
+        .unwrap();
+        let list: BanList = body.decode(1).unwrap();
+        assert_eq!(list.entries.len(), 3);
+        assert_eq!(
+            list.entries[0],
+            BanEntry {
+                id: PlayerId::Name(PlayerName::new("cheater").unwrap()),
+                timeout: Timeout::Permanent,
+                reason: "Aimbot".to_string(),
+            }
+        );
+        assert_eq!(list.entries[1].timeout, Timeout::Seconds(3540));
+        assert_eq!(list.entries[1].reason, "");
+        assert_eq!(list.entries[2].timeout, Timeout::Rounds(2));
+
+        let mut words = Vec::new();
+        list.to_words(&mut words);
+        assert_eq!(&words[..], &body.words()[1..]);
+
+        let truncated = Body::new(vec!["OK", "name", "cheater", "perm", "0", "0"]).unwrap();
+        assert_eq!(
+            truncated.decode::<BanList>(1),
+            Err(BodyError::MissingWord(6))
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use std::fmt;
+
+/// Represents a value violating the rules of a type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeError {
+    /// The length is outside of the allowed range, inclusive.
+    InvalidLength { len: usize, min: usize, max: usize },
+    /// The character is not allowed.
+    InvalidChar(char),
+    /// A hex string contains an odd number of digits.
+    OddLength(usize),
+    /// A GUID does not start with the `EA_` prefix.
+    MissingPrefix,
+    /// An id is outside of the allowed range, inclusive.
+    OutOfRange { value: u32, min: u32, max: u32 },
+    /// The value is not an integer.
+    NotAnInteger,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeError::InvalidLength { len, min, max } => write!(
+                fmt,
+                "length of {} is outside of the range {}-{}",
+                len, min, max
+            ),
+            TypeError::InvalidChar(c) => write!(fmt, "invalid character {:?}", c),
+            TypeError::OddLength(len) => write!(fmt, "odd number of hex digits ({})", len),
+            TypeError::MissingPrefix => write!(fmt, "missing \"EA_\" prefix"),
+            TypeError::OutOfRange { value, min, max } => {
+                write!(fmt, "{} is outside of the range {}-{}", value, min, max)
+            }
+            TypeError::NotAnInteger => write!(fmt, "not an integer"),
+        }
+    }
+}
+
+impl std::error::Error for TypeError {}
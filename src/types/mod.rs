@@ -0,0 +1,842 @@
+mod ban_list;
+mod error;
+mod player_info;
+mod server_info;
+mod squad;
+pub mod vars;
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+use crate::conn::{
+    word_from_string, BodyError, FromWord, FromWords, ToWord, ToWords, Word, WordReader,
+};
+
+pub use self::ban_list::{BanEntry, BanList};
+pub use self::error::TypeError;
+pub use self::player_info::{PlayerInfo, PlayerList};
+pub use self::server_info::ServerInfo;
+pub use self::squad::SquadTree;
+pub use self::vars::{ServerConfig, VarValue};
+
+const PASSWORD_MAX_LEN: usize = 16;
+const FILENAME_MIN_LEN: usize = 1;
+const FILENAME_MAX_LEN: usize = 240;
+const GUID_PREFIX: &str = "EA_";
+const GUID_HEX_LEN: usize = 32;
+const TEAM_ID_MAX: u32 = 16;
+const SQUAD_ID_MAX: u32 = 32;
+const MAP_LIST_ITEM_MIN_WORDS: usize = 3;
+
+/// A password is from 0 up to 16 characters in length, inclusive.
+// abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Password(Word);
+
+impl Password {
+    fn validate(s: &str) -> Result<(), TypeError> {
+        validate_len(s, 0, PASSWORD_MAX_LEN)?;
+        validate_chars(s, |c| c.is_ascii_alphanumeric())
+    }
+}
+
+/// A stream of hexadecimal digits.
+/// The stream must always contain an even number of digits.
+// 0123456789ABCDEF
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HexString(Word);
+
+impl HexString {
+    fn validate(s: &str) -> Result<(), TypeError> {
+        validate_chars(s, |c| c.is_ascii_hexdigit())?;
+        if s.len() & 1 != 0 {
+            return Err(TypeError::OddLength(s.len()));
+        }
+        Ok(())
+    }
+}
+
+/// A filename is from 1 up to 240 characters in length, inclusive.
+// abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789._-
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Filename(Word);
+
+impl Filename {
+    fn validate(s: &str) -> Result<(), TypeError> {
+        validate_len(s, FILENAME_MIN_LEN, FILENAME_MAX_LEN)?;
+        validate_chars(s, |c| {
+            c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-'
+        })
+    }
+}
+
+/// A clan tag is from 0 to an unknown number of characters in length.
+/// At the time of writing, it is unclear which the allowed characters are.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClanTag(Word);
+
+impl ClanTag {
+    fn validate(s: &str) -> Result<(), TypeError> {
+        validate_word_chars(s)
+    }
+}
+
+/// The "player name" (referred to as "Soldier name" in-game) is the persona
+/// name which the player chose when logging in to EA Online.
+/// The exact specification of a player name (length, valid characters, etc.)
+/// is currently unclear.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerName(Word);
+
+impl PlayerName {
+    fn validate(s: &str) -> Result<(), TypeError> {
+        validate_len(s, 1, usize::MAX)?;
+        validate_word_chars(s)
+    }
+}
+
+/// The GUID is a unique identifier for a player.
+/// It is 35 characters long, consists of the prefix "EA_" immediately
+/// followed by a 32-character HexString.
+///
+/// GUIDs compare equal regardless of the case of their hex digits.
+#[derive(Debug, Clone)]
+pub struct PlayerGuid(Word);
+
+impl PlayerGuid {
+    fn validate(s: &str) -> Result<(), TypeError> {
+        validate_len(
+            s,
+            GUID_PREFIX.len() + GUID_HEX_LEN,
+            GUID_PREFIX.len() + GUID_HEX_LEN,
+        )?;
+        if !s.starts_with(GUID_PREFIX) {
+            return Err(TypeError::MissingPrefix);
+        }
+        HexString::validate(&s[GUID_PREFIX.len()..])
+    }
+}
+
+impl PartialEq for PlayerGuid {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str().eq_ignore_ascii_case(other.as_str())
+    }
+}
+
+impl Eq for PlayerGuid {}
+
+impl Hash for PlayerGuid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.as_str().bytes() {
+            state.write_u8(byte.to_ascii_uppercase());
+        }
+    }
+}
+
+/// An integer. Team 0 is neutral.
+/// Depending on gamemode, there are up to 16 non-neutral teams, numbered 1-16.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TeamId(u32);
+
+impl TeamId {
+    /// The neutral team.
+    pub const NEUTRAL: Self = TeamId(0);
+    const MAX: u32 = TEAM_ID_MAX;
+}
+
+/// An integer. Squad 0 is "no squad".
+/// Depending on gamemode, there are up to 32 squads numbered 1-32.
+/// Note that squad IDs are local within each team; that is, to uniquely
+/// identify a squad you need to specify both a Team ID and a Squad ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SquadId(u32);
+
+impl SquadId {
+    /// No squad.
+    pub const NONE: Self = SquadId(0);
+    const MAX: u32 = SQUAD_ID_MAX;
+}
+
+/// Several commands – such as `admin.listPlayers` – take a player
+/// subset as argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerSubset {
+    /// All players on the server
+    All,
+    /// All players in the specified team
+    Team(TeamId),
+    /// All players in the specified team and squad
+    Squad(TeamId, SquadId),
+    /// One specific player
+    Player(PlayerName),
+}
+
+impl FromWords for PlayerSubset {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let index = reader.index();
+        match reader.next_word()?.as_str() {
+            "all" => Ok(PlayerSubset::All),
+            "team" => Ok(PlayerSubset::Team(reader.parse()?)),
+            "squad" => Ok(PlayerSubset::Squad(reader.parse()?, reader.parse()?)),
+            "player" => Ok(PlayerSubset::Player(reader.parse()?)),
+            _ => Err(reader.invalid(index, "PlayerSubset")),
+        }
+    }
+}
+
+impl ToWords for PlayerSubset {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        match self {
+            PlayerSubset::All => words.push(Word::new("all").unwrap()),
+            PlayerSubset::Team(team_id) => {
+                words.push(Word::new("team").unwrap());
+                words.push(team_id.to_word());
+            }
+            PlayerSubset::Squad(team_id, squad_id) => {
+                words.push(Word::new("squad").unwrap());
+                words.push(team_id.to_word());
+                words.push(squad_id.to_word());
+            }
+            PlayerSubset::Player(name) => {
+                words.push(Word::new("player").unwrap());
+                words.push(name.to_word());
+            }
+        }
+    }
+}
+
+/// Some commands, such as bans, take a timeout as argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timeout {
+    /// Permanent
+    Permanent,
+    /// Number of rounds
+    Rounds(u32),
+    /// Number of seconds
+    Seconds(u32),
+}
+
+impl Timeout {
+    /// Reads a timeout as found in ban list entries, where the timeout
+    /// type is followed by both the seconds and rounds left.
+    pub fn from_ban_list_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let index = reader.index();
+        let kind = reader.next_word()?;
+        let seconds = reader.parse()?;
+        let rounds = reader.parse()?;
+        match kind.as_str() {
+            "perm" => Ok(Timeout::Permanent),
+            "rounds" => Ok(Timeout::Rounds(rounds)),
+            "seconds" => Ok(Timeout::Seconds(seconds)),
+            _ => Err(reader.invalid(index, "Timeout")),
+        }
+    }
+
+    /// Appends the timeout as found in ban list entries.
+    pub fn to_ban_list_words(&self, words: &mut Vec<Word>) {
+        let (kind, seconds, rounds) = match *self {
+            Timeout::Permanent => ("perm", 0, 0),
+            Timeout::Rounds(rounds) => ("rounds", 0, rounds),
+            Timeout::Seconds(seconds) => ("seconds", seconds, 0),
+        };
+        words.push(Word::new(kind).unwrap());
+        words.push(seconds.to_word());
+        words.push(rounds.to_word());
+    }
+}
+
+impl FromWords for Timeout {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let index = reader.index();
+        match reader.next_word()?.as_str() {
+            "perm" => Ok(Timeout::Permanent),
+            "rounds" => Ok(Timeout::Rounds(reader.parse()?)),
+            "seconds" => Ok(Timeout::Seconds(reader.parse()?)),
+            _ => Err(reader.invalid(index, "Timeout")),
+        }
+    }
+}
+
+impl ToWords for Timeout {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        match *self {
+            Timeout::Permanent => words.push(Word::new("perm").unwrap()),
+            Timeout::Rounds(rounds) => {
+                words.push(Word::new("rounds").unwrap());
+                words.push(rounds.to_word());
+            }
+            Timeout::Seconds(seconds) => {
+                words.push(Word::new("seconds").unwrap());
+                words.push(seconds.to_word());
+            }
+        }
+    }
+}
+
+/// Some commands, such as bans, take an id-type as argument
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerId {
+    /// Soldier name
+    Name(PlayerName),
+    /// IP address
+    Ip(IpAddr),
+    /// Player’s GUID
+    Guid(PlayerGuid),
+}
+
+impl FromWords for PlayerId {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let index = reader.index();
+        match reader.next_word()?.as_str() {
+            "name" => Ok(PlayerId::Name(reader.parse()?)),
+            "guid" => Ok(PlayerId::Guid(reader.parse()?)),
+            "ip" => {
+                let index = reader.index();
+                let addr = reader.next_word()?.as_str();
+                // Addresses are usually without a port, but tolerate one.
+                addr.parse()
+                    .or_else(|_| addr.parse::<SocketAddr>().map(|addr| addr.ip()))
+                    .map(PlayerId::Ip)
+                    .map_err(|_| reader.invalid(index, "IpAddr"))
+            }
+            _ => Err(reader.invalid(index, "PlayerId")),
+        }
+    }
+}
+
+impl ToWords for PlayerId {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        match self {
+            PlayerId::Name(name) => {
+                words.push(Word::new("name").unwrap());
+                words.push(name.to_word());
+            }
+            PlayerId::Ip(ip) => {
+                words.push(Word::new("ip").unwrap());
+                words.push(ip.to_word());
+            }
+            PlayerId::Guid(guid) => {
+                words.push(Word::new("guid").unwrap());
+                words.push(guid.to_word());
+            }
+        }
+    }
+}
+
+/// This describes the number of tickets, or kills,
+/// for each team in the current round.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TeamScores {
+    /// Score for all teams
+    pub score: Vec<u32>,
+    /// When any team reaches this score, the match ends
+    pub target_score: u32,
+}
+
+/// Reads the number of teams, each team's score and the target score.
+impl FromWords for TeamScores {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let count: usize = reader.parse()?;
+        let mut score = Vec::with_capacity(count.min(TEAM_ID_MAX as usize));
+        for _ in 0..count {
+            score.push(reader.parse()?);
+        }
+        let target_score = reader.parse()?;
+        Ok(Self {
+            score,
+            target_score,
+        })
+    }
+}
+
+impl ToWords for TeamScores {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        words.push(self.score.len().to_word());
+        words.extend(self.score.iter().map(ToWord::to_word));
+        words.push(self.target_score.to_word());
+    }
+}
+
+/// The game and build of a server, as returned by `version`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    /// Game name, such as `BF3` or `BF4`
+    pub game: String,
+    /// Build number of the server
+    pub build: u32,
+}
+
+impl FromWords for Version {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        Ok(Self {
+            game: reader.parse()?,
+            build: reader.parse()?,
+        })
+    }
+}
+
+impl ToWords for Version {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        words.push(word_from_string(self.game.clone()));
+        words.push(self.build.to_word());
+    }
+}
+
+/// The indices of the current and next map in the map list, as
+/// returned by `mapList.getMapIndices`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapIndices {
+    /// Index of the map being played
+    pub current: usize,
+    /// Index of the map played after the current round
+    pub next: usize,
+}
+
+impl FromWords for MapIndices {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        Ok(Self {
+            current: reader.parse()?,
+            next: reader.parse()?,
+        })
+    }
+}
+
+impl ToWords for MapIndices {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        words.push(self.current.to_word());
+        words.push(self.next.to_word());
+    }
+}
+
+/// This describes the set of maps which the server rotates through.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MapList {
+    pub maps: Vec<MapListItem>,
+}
+
+/// Reads the number of maps and words per map, followed by the maps.
+///
+/// Each map has at least three words, and any extended words beyond
+/// those are kept in [`MapListItem::words`].
+impl FromWords for MapList {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let count: usize = reader.parse()?;
+        let words_index = reader.index();
+        let words_per_map: usize = reader.parse()?;
+        if words_per_map < MAP_LIST_ITEM_MIN_WORDS {
+            return Err(reader.invalid(words_index, "at least 3 words per map"));
+        }
+        let mut maps = Vec::with_capacity(count.min(reader.remaining().len()));
+        for _ in 0..count {
+            let map_name = reader.parse()?;
+            let game_mode = reader.parse()?;
+            let rounds = reader.parse()?;
+            let extra_words = words_per_map - MAP_LIST_ITEM_MIN_WORDS;
+            let mut words = Vec::with_capacity(extra_words.min(reader.remaining().len()));
+            for _ in MAP_LIST_ITEM_MIN_WORDS..words_per_map {
+                words.push(reader.next_word()?.clone());
+            }
+            maps.push(MapListItem {
+                map_name,
+                game_mode,
+                rounds,
+                words,
+            });
+        }
+        Ok(Self { maps })
+    }
+}
+
+/// Maps with fewer extended words than others are padded with empty
+/// words, since all maps share the same number of words.
+///
+/// # Panics
+///
+/// Panics if a map name or game mode contains characters not valid
+/// in a word.
+impl ToWords for MapList {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        let extended = self.maps.iter().map(|m| m.words.len()).max().unwrap_or(0);
+        words.push(self.maps.len().to_word());
+        words.push((MAP_LIST_ITEM_MIN_WORDS + extended).to_word());
+        for map in &self.maps {
+            words.push(word_from_string(map.map_name.clone()));
+            words.push(word_from_string(map.game_mode.clone()));
+            words.push(map.rounds.to_word());
+            words.extend(map.words.iter().cloned());
+            for _ in map.words.len()..extended {
+                words.push(Word::new("").unwrap());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapListItem {
+    /// Number of words per map
+    pub map_name: String,
+    /// Name of game mode
+    pub game_mode: String,
+    /// Number of rounds to play on map before switching
+    pub rounds: u32,
+    /// Other words if extended
+    pub words: Vec<Word>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+fn validate_len(s: &str, min: usize, max: usize) -> Result<(), TypeError> {
+    let len = s.len();
+    if len < min || len > max {
+        return Err(TypeError::InvalidLength { len, min, max });
+    }
+    Ok(())
+}
+
+fn validate_chars<F>(s: &str, is_valid: F) -> Result<(), TypeError>
+where
+    F: Fn(char) -> bool,
+{
+    match s.chars().find(|c| !is_valid(*c)) {
+        Some(c) => Err(TypeError::InvalidChar(c)),
+        None => Ok(()),
+    }
+}
+
+fn validate_word_chars(s: &str) -> Result<(), TypeError> {
+    validate_chars(s, |c| c.is_ascii() && Word::is_valid_char(c as u8))
+}
+
+macro_rules! impl_word_newtype {
+    ($($ty:ident),*) => {
+        $(
+            impl $ty {
+                /// Creates a new value, validating it against the rules of the type.
+                pub fn new(s: &str) -> Result<Self, TypeError> {
+                    Self::validate(s)?;
+                    Ok($ty(Word::new(s).expect("validated word")))
+                }
+
+                /// Returns the value as a string.
+                pub fn as_str(&self) -> &str {
+                    self.0.as_str()
+                }
+
+                /// Returns the value as a word.
+                pub fn as_word(&self) -> &Word {
+                    &self.0
+                }
+
+                /// Consumes the value, returning the word.
+                pub fn into_word(self) -> Word {
+                    self.0
+                }
+            }
+
+            impl fmt::Display for $ty {
+                fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                    fmt.write_str(self.as_str())
+                }
+            }
+
+            impl FromStr for $ty {
+                type Err = TypeError;
+
+                fn from_str(s: &str) -> Result<Self, TypeError> {
+                    Self::new(s)
+                }
+            }
+
+            impl FromWord for $ty {
+                const EXPECTED: &'static str = stringify!($ty);
+
+                fn from_word(word: &Word) -> Option<Self> {
+                    Self::validate(word.as_str()).ok()?;
+                    Some($ty(word.clone()))
+                }
+            }
+
+            impl ToWord for $ty {
+                fn to_word(&self) -> Word {
+                    self.0.clone()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_word_id {
+    ($($ty:ident),*) => {
+        $(
+            impl $ty {
+                /// Creates a new id, validating it is within the allowed range.
+                pub fn new(id: u32) -> Result<Self, TypeError> {
+                    if id > Self::MAX {
+                        return Err(TypeError::OutOfRange {
+                            value: id,
+                            min: 0,
+                            max: Self::MAX,
+                        });
+                    }
+                    Ok($ty(id))
+                }
+
+                /// Returns the id as an integer.
+                pub fn get(self) -> u32 {
+                    self.0
+                }
+            }
+
+            impl fmt::Display for $ty {
+                fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                    write!(fmt, "{}", self.0)
+                }
+            }
+
+            impl FromStr for $ty {
+                type Err = TypeError;
+
+                fn from_str(s: &str) -> Result<Self, TypeError> {
+                    Self::new(s.parse().map_err(|_| TypeError::NotAnInteger)?)
+                }
+            }
+
+            impl FromWord for $ty {
+                const EXPECTED: &'static str = stringify!($ty);
+
+                fn from_word(word: &Word) -> Option<Self> {
+                    u32::from_word(word).and_then(|id| Self::new(id).ok())
+                }
+            }
+
+            impl ToWord for $ty {
+                fn to_word(&self) -> Word {
+                    self.0.to_word()
+                }
+            }
+        )*
+    };
+}
+
+impl_word_newtype!(Password, HexString, Filename, ClanTag, PlayerName, PlayerGuid);
+impl_word_id!(TeamId, SquadId);
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conn::Body;
+
+    #[test]
+    fn password_test() {
+        assert!(Password::new("").is_ok());
+        assert!(Password::new("abcDEF0123456789").is_ok());
+        assert_eq!(
+            Password::new("abcDEF01234567890"),
+            Err(TypeError::InvalidLength {
+                len: 17,
+                min: 0,
+                max: 16
+            })
+        );
+        assert_eq!(Password::new("pass word"), Err(TypeError::InvalidChar(' ')));
+    }
+
+    #[test]
+    fn filename_test() {
+        assert!(Filename::new("banList_2019-10.txt").is_ok());
+        assert!(Filename::new("").is_err());
+        assert_eq!(Filename::new("a/b"), Err(TypeError::InvalidChar('/')));
+        assert!(Filename::new(&"a".repeat(240)).is_ok());
+        assert!(Filename::new(&"a".repeat(241)).is_err());
+    }
+
+    #[test]
+    fn hex_string_test() {
+        assert!(HexString::new("00FF").is_ok());
+        assert_eq!(HexString::new("0FF"), Err(TypeError::OddLength(3)));
+        assert_eq!(HexString::new("0G"), Err(TypeError::InvalidChar('G')));
+    }
+
+    #[test]
+    fn player_guid_test() {
+        let upper = PlayerGuid::new("EA_0123456789ABCDEF0123456789ABCDEF").unwrap();
+        let lower: PlayerGuid = "EA_0123456789abcdef0123456789abcdef".parse().unwrap();
+        assert_eq!(upper, lower);
+        assert_eq!(
+            PlayerGuid::new("XX_0123456789ABCDEF0123456789ABCDEF"),
+            Err(TypeError::MissingPrefix)
+        );
+        assert!(PlayerGuid::new("EA_0123").is_err());
+    }
+
+    #[test]
+    fn id_test() {
+        assert_eq!(TeamId::new(16).map(TeamId::get), Ok(16));
+        assert_eq!(
+            TeamId::new(17),
+            Err(TypeError::OutOfRange {
+                value: 17,
+                min: 0,
+                max: 16
+            })
+        );
+        assert_eq!("32".parse::<SquadId>().map(SquadId::get), Ok(32));
+        assert!("33".parse::<SquadId>().is_err());
+        assert_eq!("x".parse::<SquadId>(), Err(TypeError::NotAnInteger));
+        assert_eq!(SquadId::NONE.to_string(), "0");
+    }
+
+    fn round_trip<T>(value: T, expected: &[&str])
+    where
+        T: FromWords + ToWords + PartialEq + fmt::Debug,
+    {
+        let mut words = Vec::new();
+        value.to_words(&mut words);
+        let strs: Vec<&str> = words.iter().map(Word::as_str).collect();
+        assert_eq!(&strs[..], expected);
+        let mut reader = WordReader::new(&words);
+        assert_eq!(reader.decode::<T>().unwrap(), value);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn player_subset_words_test() {
+        let team = TeamId::new(1).unwrap();
+        let squad = SquadId::new(2).unwrap();
+        round_trip(PlayerSubset::All, &["all"]);
+        round_trip(PlayerSubset::Team(team), &["team", "1"]);
+        round_trip(PlayerSubset::Squad(team, squad), &["squad", "1", "2"]);
+        round_trip(
+            PlayerSubset::Player(PlayerName::new("avitex").unwrap()),
+            &["player", "avitex"],
+        );
+    }
+
+    #[test]
+    fn timeout_words_test() {
+        round_trip(Timeout::Permanent, &["perm"]);
+        round_trip(Timeout::Rounds(3), &["rounds", "3"]);
+        round_trip(Timeout::Seconds(600), &["seconds", "600"]);
+
+        let words: Vec<Word> = ["seconds", "598", "0", "rounds", "0", "2"]
+            .iter()
+            .map(|w| Word::new(w).unwrap())
+            .collect();
+        let mut reader = WordReader::new(&words);
+        assert_eq!(
+            Timeout::from_ban_list_words(&mut reader),
+            Ok(Timeout::Seconds(598))
+        );
+        assert_eq!(
+            Timeout::from_ban_list_words(&mut reader),
+            Ok(Timeout::Rounds(2))
+        );
+    }
+
+    fn words(words: &[&str]) -> Vec<Word> {
+        words.iter().map(|w| Word::new(w).unwrap()).collect()
+    }
+
+    #[test]
+    fn team_scores_test() {
+        // From `server.onRoundOverTeamScores`
+        let captured = words(&["2", "0", "12", "0"]);
+        let scores: TeamScores = WordReader::new(&captured).decode().unwrap();
+        assert_eq!(scores.score, [0, 12]);
+        assert_eq!(scores.target_score, 0);
+        round_trip(scores, &["2", "0", "12", "0"]);
+
+        // From `serverInfo`, cut short after the second team
+        let truncated = words(&["2", "800", "734"]);
+        assert_eq!(
+            WordReader::new(&truncated).decode::<TeamScores>(),
+            Err(BodyError::MissingWord(3))
+        );
+    }
+
+    #[test]
+    fn map_list_test() {
+        let body = Body::new(vec![
+            "OK",
+            "2",
+            "3",
+            "MP_Prison",
+            "ConquestLarge0",
+            "2",
+            "XP0_Metro",
+            "RushLarge0",
+            "1",
+        ])
+        .unwrap();
+        let list: MapList = body.decode(1).unwrap();
+        assert_eq!(list.maps.len(), 2);
+        assert_eq!(list.maps[1].map_name, "XP0_Metro");
+        assert_eq!(list.maps[1].game_mode, "RushLarge0");
+        assert_eq!(list.maps[1].rounds, 1);
+        let expected: Vec<&str> = body.words()[1..].iter().map(Word::as_str).collect();
+        round_trip(list, &expected);
+
+        let extended = words(&["1", "4", "MP_Abandoned", "Domination0", "2", "x"]);
+        let list: MapList = WordReader::new(&extended).decode().unwrap();
+        assert_eq!(list.maps[0].words, words(&["x"]));
+
+        let truncated = words(&["2", "3", "MP_Prison", "ConquestLarge0", "2", "XP0_Metro"]);
+        assert_eq!(
+            WordReader::new(&truncated).decode::<MapList>(),
+            Err(BodyError::MissingWord(6))
+        );
+        let huge = words(&[
+            "1",
+            "18446744073709551615",
+            "MP_Prison",
+            "ConquestLarge0",
+            "2",
+        ]);
+        assert_eq!(
+            WordReader::new(&huge).decode::<MapList>(),
+            Err(BodyError::MissingWord(5))
+        );
+        let too_few = words(&["1", "2", "MP_Prison", "ConquestLarge0"]);
+        assert_eq!(
+            WordReader::new(&too_few).decode::<MapList>(),
+            Err(BodyError::InvalidWord {
+                index: 1,
+                expected: "at least 3 words per map"
+            })
+        );
+    }
+
+    #[test]
+    fn player_id_words_test() {
+        round_trip(
+            PlayerId::Ip("10.0.0.1".parse().unwrap()),
+            &["ip", "10.0.0.1"],
+        );
+        round_trip(PlayerId::Ip("::1".parse().unwrap()), &["ip", "::1"]);
+        round_trip(
+            PlayerId::Guid(PlayerGuid::new("EA_0123456789ABCDEF0123456789ABCDEF").unwrap()),
+            &["guid", "EA_0123456789ABCDEF0123456789ABCDEF"],
+        );
+
+        let words = vec![
+            Word::new("ip").unwrap(),
+            Word::new("10.0.0.1:3659").unwrap(),
+        ];
+        assert_eq!(
+            WordReader::new(&words).decode::<PlayerId>(),
+            Ok(PlayerId::Ip("10.0.0.1".parse().unwrap()))
+        );
+        let words = vec![Word::new("uid").unwrap(), Word::new("x").unwrap()];
+        assert_eq!(
+            WordReader::new(&words).decode::<PlayerId>(),
+            Err(BodyError::InvalidWord {
+                index: 0,
+                expected: "PlayerId"
+            })
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{PlayerGuid, PlayerName, SquadId, TeamId};
+use crate::conn::{BodyError, FromWords, ToWord, ToWords, Word, WordReader};
+use crate::profile::GameProfile;
+
+const NAME: &str = "name";
+const GUID: &str = "guid";
+const TEAM_ID: &str = "teamId";
+const SQUAD_ID: &str = "squadId";
+const KILLS: &str = "kills";
+const DEATHS: &str = "deaths";
+const SCORE: &str = "score";
+const RANK: &str = "rank";
+const PING: &str = "ping";
+const TYPE: &str = "type";
+
+/// Columns every player info block must carry, with the error
+/// reported when one is absent.
+const REQUIRED_COLUMNS: &[(&str, &str)] = &[
+    (NAME, "column \"name\""),
+    (TEAM_ID, "column \"teamId\""),
+    (SQUAD_ID, "column \"squadId\""),
+    (KILLS, "column \"kills\""),
+    (DEATHS, "column \"deaths\""),
+    (SCORE, "column \"score\""),
+];
+
+/// The standard set of info for a group of players contains a lot
+/// of different fields. To reduce the risk of having to do
+/// backwards-incompatible changes to the protocol, the player info
+/// block includes some formatting information.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerInfo {
+    /// Player name
+    pub name: PlayerName,
+    /// Player's GUID, empty when not known to the server
+    pub guid: Option<PlayerGuid>,
+    /// Player's current team
+    pub team_id: TeamId,
+    /// Player's current squad
+    pub squad_id: SquadId,
+    /// Number of kills, as shown in the in-game scoreboard
+    pub kills: u32,
+    /// Number of deaths, as shown in the in-game scoreboard
+    pub deaths: u32,
+    /// Score, as shown in the in-game scoreboard
+    pub score: u32,
+    /// The rank of the player
+    pub rank: Option<u32>,
+    /// Ping between the server and player
+    pub ping: Option<u32>,
+    /// Player type, such as spectator or commander (BF4 only)
+    pub player_type: Option<u32>,
+    /// Columns not known to this crate, by column name
+    pub extras: BTreeMap<String, Word>,
+}
+
+/// A player info block, as returned by `admin.listPlayers` and
+/// `server.onRoundOverPlayers`.
+///
+/// The block starts with the number of columns and their names,
+/// followed by the number of players and a row of words per player.
+/// Columns are matched by name, so their order does not matter.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PlayerList {
+    pub players: Vec<PlayerInfo>,
+}
+
+impl PlayerList {
+    /// Writes a player info block with the columns of the game's
+    /// dialect, followed by every extra column, in name order.
+    pub fn encode(&self, profile: GameProfile, words: &mut Vec<Word>) {
+        let mut columns = profile.player_info_columns().to_vec();
+        columns.extend(self.extra_columns());
+        self.write_block(&columns, words);
+    }
+
+    fn extra_columns(&self) -> BTreeSet<&str> {
+        self.players
+            .iter()
+            .flat_map(|p| p.extras.keys().map(String::as_str))
+            .collect()
+    }
+
+    fn write_block(&self, columns: &[&str], words: &mut Vec<Word>) {
+        words.push(columns.len().to_word());
+        for column in columns {
+            words.push(Word::new(column).unwrap());
+        }
+        words.push(self.players.len().to_word());
+        for player in &self.players {
+            for column in columns {
+                words.push(match *column {
+                    NAME => player.name.to_word(),
+                    GUID => player.guid.to_word(),
+                    TEAM_ID => player.team_id.to_word(),
+                    SQUAD_ID => player.squad_id.to_word(),
+                    KILLS => player.kills.to_word(),
+                    DEATHS => player.deaths.to_word(),
+                    SCORE => player.score.to_word(),
+                    RANK => player.rank.to_word(),
+                    PING => player.ping.to_word(),
+                    TYPE => player.player_type.to_word(),
+                    extra => player.extras.get(extra).cloned().to_word(),
+                });
+            }
+        }
+    }
+}
+
+/// Reads a player info block of any game, whichever optional columns
+/// it carries.
+impl FromWords for PlayerList {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let header_index = reader.index();
+        let column_count: usize = reader.parse()?;
+        let mut columns = Vec::with_capacity(column_count.min(reader.remaining().len()));
+        for _ in 0..column_count {
+            columns.push(reader.next_word()?.as_str());
+        }
+        for (column, expected) in REQUIRED_COLUMNS {
+            if !columns.contains(column) {
+                return Err(reader.invalid(header_index, expected));
+            }
+        }
+        let player_count: usize = reader.parse()?;
+        let mut players = Vec::with_capacity(player_count.min(reader.remaining().len()));
+        for _ in 0..player_count {
+            players.push(read_row(reader, &columns)?);
+        }
+        Ok(Self { players })
+    }
+}
+
+fn read_row(reader: &mut WordReader<'_>, columns: &[&str]) -> Result<PlayerInfo, BodyError> {
+    let (mut name, mut team_id, mut squad_id) = (None, None, None);
+    let (mut kills, mut deaths, mut score) = (None, None, None);
+    let (mut guid, mut rank, mut ping, mut player_type) = (None, None, None, None);
+    let mut extras = BTreeMap::new();
+    for column in columns {
+        match *column {
+            NAME => name = Some(reader.parse()?),
+            GUID => guid = reader.parse()?,
+            TEAM_ID => team_id = Some(reader.parse()?),
+            SQUAD_ID => squad_id = Some(reader.parse()?),
+            KILLS => kills = Some(reader.parse()?),
+            DEATHS => deaths = Some(reader.parse()?),
+            SCORE => score = Some(reader.parse()?),
+            RANK => rank = reader.parse()?,
+            PING => ping = reader.parse()?,
+            TYPE => player_type = reader.parse()?,
+            _ => {
+                extras.insert(column.to_string(), reader.next_word()?.clone());
+            }
+        }
+    }
+    // Required columns were checked against the header.
+    Ok(PlayerInfo {
+        name: name.unwrap(),
+        guid,
+        team_id: team_id.unwrap(),
+        squad_id: squad_id.unwrap(),
+        kills: kills.unwrap(),
+        deaths: deaths.unwrap(),
+        score: score.unwrap(),
+        rank,
+        ping,
+        player_type,
+        extras,
+    })
+}
+
+/// Writes the standard columns, the optional columns any player has
+/// a value for, and then every extra column, in name order.
+impl ToWords for PlayerList {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        let has_rank = self.players.iter().any(|p| p.rank.is_some());
+        let has_ping = self.players.iter().any(|p| p.ping.is_some());
+        let has_type = self.players.iter().any(|p| p.player_type.is_some());
+        let mut columns = vec![NAME, GUID, TEAM_ID, SQUAD_ID, KILLS, DEATHS, SCORE];
+        if has_rank {
+            columns.push(RANK);
+        }
+        if has_ping {
+            columns.push(PING);
+        }
+        if has_type {
+            columns.push(TYPE);
+        }
+        columns.extend(self.extra_columns());
+        self.write_block(&columns, words);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conn::Body;
+
+    const GUID_A: &str = "EA_0123456789ABCDEF0123456789ABCDEF";
+
+    fn words(words: &[&str]) -> Vec<Word> {
+        words.iter().map(|w| Word::new(w).unwrap()).collect()
+    }
+
+    #[test]
+    fn decode_bf4_test() {
+        let body = Body::new(vec![
+            "OK", "10", "name", "guid", "teamId", "squadId", "kills", "deaths", "score", "rank",
+            "ping", "type", "1", "avitex", GUID_A, "1", "2", "10", "4", "1200", "42", "35", "0",
+        ])
+        .unwrap();
+        let list: PlayerList = body.decode(1).unwrap();
+        assert_eq!(list.players.len(), 1);
+        let player = &list.players[0];
+        assert_eq!(player.name.as_str(), "avitex");
+        assert_eq!(player.guid.as_ref().map(PlayerGuid::as_str), Some(GUID_A));
+        assert_eq!(player.team_id, TeamId::new(1).unwrap());
+        assert_eq!(player.squad_id, SquadId::new(2).unwrap());
+        assert_eq!((player.kills, player.deaths, player.score), (10, 4, 1200));
+        assert_eq!(player.rank, Some(42));
+        assert_eq!(player.ping, Some(35));
+        assert_eq!(player.player_type, Some(0));
+        assert!(player.extras.is_empty());
+    }
+
+    #[test]
+    fn decode_reordered_with_extras_test() {
+        let words = words(&[
+            "8", "score", "name", "kills", "deaths", "squadId", "teamId", "guid", "flag", "2",
+            "100", "a", "1", "0", "0", "1", "", "x", "50", "b", "0", "3", "1", "2", "", "y",
+        ]);
+        let list: PlayerList = WordReader::new(&words).decode().unwrap();
+        let names: Vec<&str> = list.players.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(list.players[0].guid, None);
+        assert_eq!(list.players[0].rank, None);
+        assert_eq!(list.players[1].deaths, 3);
+        assert_eq!(list.players[1].extras["flag"].as_str(), "y");
+    }
+
+    #[test]
+    fn decode_errors_test() {
+        let missing_column = words(&["2", "name", "teamId", "0"]);
+        assert_eq!(
+            WordReader::new(&missing_column).decode::<PlayerList>(),
+            Err(BodyError::InvalidWord {
+                index: 0,
+                expected: "column \"squadId\""
+            })
+        );
+        let truncated = words(&[
+            "6", "name", "teamId", "squadId", "kills", "deaths", "score", "1", "a", "1", "1",
+        ]);
+        assert_eq!(
+            WordReader::new(&truncated).decode::<PlayerList>(),
+            Err(BodyError::MissingWord(11))
+        );
+        let huge_counts = words(&["18446744073709551615", "name"]);
+        assert_eq!(
+            WordReader::new(&huge_counts).decode::<PlayerList>(),
+            Err(BodyError::MissingWord(2))
+        );
+        let huge_players = words(&[
+            "6",
+            "name",
+            "teamId",
+            "squadId",
+            "kills",
+            "deaths",
+            "score",
+            "18446744073709551615",
+        ]);
+        assert_eq!(
+            WordReader::new(&huge_players).decode::<PlayerList>(),
+            Err(BodyError::MissingWord(8))
+        );
+    }
+
+    #[test]
+    fn profile_test() {
+        let bf3 = words(&[
+            "9", "name", "guid", "teamId", "squadId", "kills", "deaths", "score", "rank", "ping",
+            "1", "a", "", "1", "1", "0", "0", "0", "12", "30",
+        ]);
+        // Decoding does not depend on the game's dialect.
+        let list: PlayerList = WordReader::new(&bf3).decode().unwrap();
+        assert_eq!(list.players[0].rank, Some(12));
+        assert_eq!(list.players[0].player_type, None);
+
+        let mut encoded = Vec::new();
+        list.encode(GameProfile::Bf4, &mut encoded);
+        assert_eq!(encoded[0].as_str(), "10");
+        assert_eq!(encoded[10].as_str(), "type");
+        assert_eq!(encoded[21].as_str(), "");
+        let mut encoded = Vec::new();
+        list.encode(GameProfile::Bf3, &mut encoded);
+        assert_eq!(encoded, bf3);
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let mut extras = BTreeMap::new();
+        extras.insert("flag".to_string(), Word::new("x").unwrap());
+        let list = PlayerList {
+            players: vec![
+                PlayerInfo {
+                    name: PlayerName::new("a").unwrap(),
+                    guid: Some(PlayerGuid::new(GUID_A).unwrap()),
+                    team_id: TeamId::new(1).unwrap(),
+                    squad_id: SquadId::new(1).unwrap(),
+                    kills: 1,
+                    deaths: 2,
+                    score: 3,
+                    rank: None,
+                    ping: Some(20),
+                    player_type: None,
+                    extras,
+                },
+                PlayerInfo {
+                    name: PlayerName::new("b").unwrap(),
+                    guid: None,
+                    team_id: TeamId::new(2).unwrap(),
+                    squad_id: SquadId::NONE,
+                    kills: 0,
+                    deaths: 0,
+                    score: 0,
+                    rank: None,
+                    ping: None,
+                    player_type: None,
+                    extras: BTreeMap::new(),
+                },
+            ],
+        };
+        let mut words = Vec::new();
+        list.to_words(&mut words);
+        assert_eq!(words[0].as_str(), "9");
+        assert_eq!(words[8].as_str(), "ping");
+        assert_eq!(words[9].as_str(), "flag");
+        let decoded: PlayerList = WordReader::new(&words).decode().unwrap();
+        // Extra columns absent for a player come back as empty words.
+        assert_eq!(decoded.players[0], list.players[0]);
+        assert_eq!(decoded.players[1].extras["flag"].as_str(), "");
+    }
+}
@@ -0,0 +1,243 @@
+use super::TeamScores;
+use crate::conn::{
+    word_from_string, BodyError, FromWord, FromWords, ToWord, ToWords, Word, WordReader,
+};
+
+/// The response to `serverInfo`.
+///
+/// Fields following the team scores were appended over several game
+/// patches, so older servers may omit them and are read as `None`, as
+/// are empty words. Words following the last known field are kept in
+/// `extra` rather than rejected.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ServerInfo {
+    /// Server name
+    pub server_name: String,
+    /// Current number of players
+    pub player_count: u32,
+    /// Maximum number of players
+    pub max_player_count: u32,
+    /// Current game mode
+    pub game_mode: String,
+    /// Current map
+    pub map: String,
+    /// Rounds played on the current map
+    pub rounds_played: u32,
+    /// Total rounds to play on the current map
+    pub rounds_total: u32,
+    /// Current team scores
+    pub scores: TeamScores,
+    /// Online state, empty unless the server is offline
+    pub online_state: Option<String>,
+    /// Whether the server is ranked
+    pub ranked: Option<bool>,
+    /// Whether punkbuster is enabled
+    pub punkbuster: Option<bool>,
+    /// Whether a game password is set
+    pub has_game_password: Option<bool>,
+    /// Server uptime, in seconds
+    pub server_uptime: Option<u32>,
+    /// Time of the current round, in seconds
+    pub round_time: Option<u32>,
+    /// Game server IP address and port
+    pub game_address: Option<String>,
+    /// Punkbuster version
+    pub punkbuster_version: Option<String>,
+    /// Whether the join queue is enabled
+    pub join_queue_enabled: Option<bool>,
+    /// Server region
+    pub region: Option<String>,
+    /// Closest ping site
+    pub closest_ping_site: Option<String>,
+    /// Server country
+    pub country: Option<String>,
+    /// Whether matchmaking is enabled (BF3 only)
+    pub matchmaking_enabled: Option<bool>,
+    /// Number of players according to the backend (BF4 only)
+    pub blaze_player_count: Option<u32>,
+    /// Game state according to the backend (BF4 only)
+    pub blaze_game_state: Option<String>,
+    /// Words following the last known field
+    pub extra: Vec<Word>,
+}
+
+/// Reads a trailing field, which is `None` if absent or empty.
+fn optional<T: FromWord>(reader: &mut WordReader<'_>) -> Result<Option<T>, BodyError> {
+    if reader.is_empty() {
+        Ok(None)
+    } else {
+        reader.parse()
+    }
+}
+
+impl FromWords for ServerInfo {
+    fn from_words(reader: &mut WordReader<'_>) -> Result<Self, BodyError> {
+        let mut info = ServerInfo {
+            server_name: reader.parse()?,
+            player_count: reader.parse()?,
+            max_player_count: reader.parse()?,
+            game_mode: reader.parse()?,
+            map: reader.parse()?,
+            rounds_played: reader.parse()?,
+            rounds_total: reader.parse()?,
+            scores: reader.decode()?,
+            online_state: optional(reader)?,
+            ranked: optional(reader)?,
+            punkbuster: optional(reader)?,
+            has_game_password: optional(reader)?,
+            server_uptime: optional(reader)?,
+            round_time: optional(reader)?,
+            game_address: optional(reader)?,
+            punkbuster_version: optional(reader)?,
+            join_queue_enabled: optional(reader)?,
+            region: optional(reader)?,
+            closest_ping_site: optional(reader)?,
+            country: optional(reader)?,
+            ..ServerInfo::default()
+        };
+        // BF3 follows the country with the matchmaking flag, whereas
+        // BF4 follows it with the backend player count and game state.
+        match reader.peek() {
+            Some(word) if bool::from_word(word).is_some() => {
+                info.matchmaking_enabled = optional(reader)?;
+            }
+            Some(_) => {
+                info.blaze_player_count = optional(reader)?;
+                info.blaze_game_state = optional(reader)?;
+            }
+            None => {}
+        }
+        while let Ok(word) = reader.next_word() {
+            info.extra.push(word.clone());
+        }
+        Ok(info)
+    }
+}
+
+/// Trailing fields are written up to the last one present, with any
+/// absent fields before it written as empty words.
+///
+/// # Panics
+///
+/// Panics if a string field contains characters not valid in a word.
+impl ToWords for ServerInfo {
+    fn to_words(&self, words: &mut Vec<Word>) {
+        fn string(s: &str) -> Word {
+            word_from_string(s.to_string())
+        }
+        words.push(string(&self.server_name));
+        words.push(self.player_count.to_word());
+        words.push(self.max_player_count.to_word());
+        words.push(string(&self.game_mode));
+        words.push(string(&self.map));
+        words.push(self.rounds_played.to_word());
+        words.push(self.rounds_total.to_word());
+        self.scores.to_words(words);
+
+        let mut trailing = vec![
+            self.online_state.as_ref().map(|s| string(s)),
+            self.ranked.map(|v| v.to_word()),
+            self.punkbuster.map(|v| v.to_word()),
+            self.has_game_password.map(|v| v.to_word()),
+            self.server_uptime.map(|v| v.to_word()),
+            self.round_time.map(|v| v.to_word()),
+            self.game_address.as_ref().map(|s| string(s)),
+            self.punkbuster_version.as_ref().map(|s| string(s)),
+            self.join_queue_enabled.map(|v| v.to_word()),
+            self.region.as_ref().map(|s| string(s)),
+            self.closest_ping_site.as_ref().map(|s| string(s)),
+            self.country.as_ref().map(|s| string(s)),
+        ];
+        if self.matchmaking_enabled.is_some() {
+            trailing.push(self.matchmaking_enabled.map(|v| v.to_word()));
+        } else {
+            trailing.push(self.blaze_player_count.map(|v| v.to_word()));
+            trailing.push(self.blaze_game_state.as_ref().map(|s| string(s)));
+        }
+        trailing.extend(self.extra.iter().cloned().map(Some));
+        while let Some(None) = trailing.last() {
+            trailing.pop();
+        }
+        words.extend(trailing.into_iter().map(|word| word.to_word()));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conn::Body;
+
+    const BF4: &[&str] = &[
+        "OK",
+        "My Server",
+        "10",
+        "64",
+        "ConquestLarge0",
+        "MP_Prison",
+        "0",
+        "2",
+        "2",
+        "800",
+        "734",
+        "0",
+        "",
+        "true",
+        "true",
+        "false",
+        "12345",
+        "3400",
+        "",
+        "",
+        "",
+        "EU",
+        "ams",
+        "NL",
+        "10",
+        "IN_GAME",
+    ];
+
+    #[test]
+    fn decode_bf4_test() {
+        let body = Body::new(BF4.to_vec()).unwrap();
+        let info: ServerInfo = body.decode(1).unwrap();
+        assert_eq!(info.server_name, "My Server");
+        assert_eq!((info.player_count, info.max_player_count), (10, 64));
+        assert_eq!(info.map, "MP_Prison");
+        assert_eq!(info.scores.score, [800, 734]);
+        assert_eq!(info.online_state, None);
+        assert_eq!(info.ranked, Some(true));
+        assert_eq!(info.server_uptime, Some(12345));
+        assert_eq!(info.game_address, None);
+        assert_eq!(info.country.as_deref(), Some("NL"));
+        assert_eq!(info.matchmaking_enabled, None);
+        assert_eq!(info.blaze_player_count, Some(10));
+        assert_eq!(info.blaze_game_state.as_deref(), Some("IN_GAME"));
+        assert!(info.extra.is_empty());
+
+        let mut words = Vec::new();
+        info.to_words(&mut words);
+        assert_eq!(Body::from(words).words(), &body.words()[1..]);
+    }
+
+    #[test]
+    fn decode_bf3_and_older_test() {
+        let mut bf3 = BF4[1..24].to_vec();
+        bf3.extend(&["false", "future"]);
+        let body = Body::new(bf3).unwrap();
+        let info: ServerInfo = body.decode(0).unwrap();
+        assert_eq!(info.matchmaking_enabled, Some(false));
+        assert_eq!(info.blaze_player_count, None);
+        assert_eq!(info.extra, [Word::new("future").unwrap()]);
+
+        let body = Body::new(BF4[1..13].to_vec()).unwrap();
+        let info: ServerInfo = body.decode(0).unwrap();
+        assert_eq!(info.rounds_total, 2);
+        assert_eq!(info.ranked, None);
+        assert_eq!(info.country, None);
+
+        let body = Body::new(BF4[1..10].to_vec()).unwrap();
+        assert_eq!(body.decode::<ServerInfo>(0), Err(BodyError::MissingWord(9)));
+    }
+}
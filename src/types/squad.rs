@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use super::{PlayerInfo, PlayerList, SquadId, TeamId};
+
+/// Players grouped by team and then squad.
+///
+/// Players without a squad are grouped under [`SquadId::NONE`], and
+/// keep the order of the player list within their squad.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SquadTree {
+    pub teams: BTreeMap<TeamId, BTreeMap<SquadId, Vec<PlayerInfo>>>,
+}
+
+impl SquadTree {
+    pub fn new(players: impl IntoIterator<Item = PlayerInfo>) -> Self {
+        let mut teams: BTreeMap<_, BTreeMap<_, Vec<_>>> = BTreeMap::new();
+        for player in players {
+            teams
+                .entry(player.team_id)
+                .or_default()
+                .entry(player.squad_id)
+                .or_default()
+                .push(player);
+        }
+        Self { teams }
+    }
+
+    /// Returns the players of a squad.
+    pub fn squad(&self, team_id: TeamId, squad_id: SquadId) -> &[PlayerInfo] {
+        self.teams
+            .get(&team_id)
+            .and_then(|squads| squads.get(&squad_id))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the squads of every team, excluding players without a
+    /// squad.
+    pub fn squads(&self) -> impl Iterator<Item = (TeamId, SquadId, &[PlayerInfo])> {
+        self.teams.iter().flat_map(|(team_id, squads)| {
+            squads
+                .iter()
+                .filter(|(squad_id, _)| **squad_id != SquadId::NONE)
+                .map(move |(squad_id, players)| (*team_id, *squad_id, players.as_slice()))
+        })
+    }
+}
+
+impl From<PlayerList> for SquadTree {
+    fn from(list: PlayerList) -> Self {
+        Self::new(list.players)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PlayerName;
+
+    fn player(name: &str, team_id: u32, squad_id: u32) -> PlayerInfo {
+        PlayerInfo {
+            name: PlayerName::new(name).unwrap(),
+            guid: None,
+            team_id: TeamId::new(team_id).unwrap(),
+            squad_id: SquadId::new(squad_id).unwrap(),
+            kills: 0,
+            deaths: 0,
+            score: 0,
+            rank: None,
+            ping: None,
+            player_type: None,
+            extras: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn squad_tree_test() {
+        let tree = SquadTree::new(vec![
+            player("a", 1, 1),
+            player("b", 2, 1),
+            player("c", 1, 1),
+            player("d", 1, 0),
+            player("e", 1, 3),
+        ]);
+        let team = TeamId::new(1).unwrap();
+        let names: Vec<&str> = tree
+            .squad(team, SquadId::new(1).unwrap())
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, ["a", "c"]);
+        assert_eq!(tree.squad(team, SquadId::NONE).len(), 1);
+        assert!(tree.squad(team, SquadId::new(2).unwrap()).is_empty());
+
+        let squads: Vec<(u32, u32, usize)> = tree
+            .squads()
+            .map(|(t, s, players)| (t.get(), s.get(), players.len()))
+            .collect();
+        assert_eq!(squads, [(1, 1, 2), (1, 3, 1), (2, 1, 1)]);
+    }
+}
@@ -0,0 +1,501 @@
+//! Descriptions of the server variables set through `vars.*`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::catalogue;
+use crate::conn::{word_from_string, BodyError, ToWord, Word, WordReader};
+
+/// The number of teams whose faction can be overridden.
+const TEAM_FACTIONS_MAX: usize = 4;
+
+/// The type of value a variable holds, and the values it accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarType {
+    Bool,
+    /// An integer within the range, inclusive
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// A string of at most the length
+    String {
+        max_len: usize,
+    },
+    /// One of a set of names, compared case-insensitively
+    Choice(&'static [&'static str]),
+    /// The faction of each team, set one team at a time
+    TeamFactions,
+}
+
+/// When a variable can be changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarAccess {
+    /// Can be changed at any time
+    ReadWrite,
+    /// Can only be changed before the server starts, such as from its
+    /// startup script, and needs a restart to take effect otherwise
+    Startup,
+    /// Can never be changed
+    ReadOnly,
+}
+
+/// Describes a server variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VarSpec {
+    /// Name of the variable, without the `vars.` prefix
+    pub name: &'static str,
+    pub ty: VarType,
+    pub access: VarAccess,
+}
+
+/// The value of a server variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VarValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+    /// Faction ids, indexed by team id minus one
+    TeamFactions(Vec<u32>),
+}
+
+/// Represents a variable or value rejected by the registry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VarError {
+    /// The variable is not in the registry.
+    UnknownVar(String),
+    /// The variable can never be changed.
+    ReadOnly(&'static str),
+    /// The value is not of the variable's type.
+    TypeMismatch { name: &'static str },
+    /// The integer is outside of the allowed range, inclusive.
+    OutOfRange {
+        name: &'static str,
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+    /// The string, or list of team factions, is longer than allowed.
+    TooLong {
+        name: &'static str,
+        len: usize,
+        max: usize,
+    },
+    /// The string is not a valid word, or not one of the choices, or a
+    /// faction is not in the catalogue.
+    InvalidValue { name: &'static str },
+}
+
+impl fmt::Display for VarError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VarError::UnknownVar(name) => write!(fmt, "unknown variable {:?}", name),
+            VarError::ReadOnly(name) => write!(fmt, "variable {:?} is read-only", name),
+            VarError::TypeMismatch { name } => {
+                write!(fmt, "value is not of the type of variable {:?}", name)
+            }
+            VarError::OutOfRange {
+                name,
+                value,
+                min,
+                max,
+            } => write!(
+                fmt,
+                "{} is outside of the range {}-{} of variable {:?}",
+                value, min, max, name
+            ),
+            VarError::TooLong { name, len, max } => write!(
+                fmt,
+                "length of {} exceeds the maximum {} of variable {:?}",
+                len, max, name
+            ),
+            VarError::InvalidValue { name } => write!(fmt, "invalid value of variable {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for VarError {}
+
+const PRESETS: &[&str] = &["Normal", "Hardcore", "Infantry", "Custom"];
+const UNLOCK_MODES: &[&str] = &["none", "all", "common", "stats"];
+const PERCENT: VarType = VarType::Integer { min: 0, max: 1000 };
+
+macro_rules! vars {
+    ($($name:expr => $ty:expr, $access:ident;)*) => {
+        &[$(VarSpec { name: $name, ty: $ty, access: VarAccess::$access }),*]
+    };
+}
+
+/// The BF4 server variables, in name order.
+pub const VARS: &[VarSpec] = vars! {
+    "3dSpotting" => VarType::Bool, ReadWrite;
+    "3pCam" => VarType::Bool, ReadWrite;
+    "alwaysAllowSpectators" => VarType::Bool, Startup;
+    "autoBalance" => VarType::Bool, ReadWrite;
+    "bulletDamage" => VarType::Integer { min: 0, max: 300 }, ReadWrite;
+    "commander" => VarType::Bool, Startup;
+    "forceReloadWholeMags" => VarType::Bool, ReadWrite;
+    "friendlyFire" => VarType::Bool, ReadWrite;
+    "gameModeCounter" => PERCENT, ReadWrite;
+    "gamePassword" => VarType::String { max_len: 16 }, ReadWrite;
+    "hitIndicatorsEnabled" => VarType::Bool, ReadWrite;
+    "hud" => VarType::Bool, ReadWrite;
+    "idleBanRounds" => VarType::Integer { min: 0, max: 100 }, ReadWrite;
+    "idleTimeout" => VarType::Integer { min: 0, max: 86400 }, ReadWrite;
+    "killCam" => VarType::Bool, ReadWrite;
+    "maxPlayers" => VarType::Integer { min: 1, max: 70 }, ReadWrite;
+    "maxSpectators" => VarType::Integer { min: 0, max: 4 }, ReadWrite;
+    "miniMap" => VarType::Bool, ReadWrite;
+    "miniMapSpotting" => VarType::Bool, ReadWrite;
+    "mpExperience" => VarType::String { max_len: 64 }, ReadOnly;
+    "nameTag" => VarType::Bool, ReadWrite;
+    "onlySquadLeaderSpawn" => VarType::Bool, ReadWrite;
+    "playerManDownTime" => PERCENT, ReadWrite;
+    "playerRespawnTime" => PERCENT, ReadWrite;
+    "preset" => VarType::Choice(PRESETS), ReadWrite;
+    "regenerateHealth" => VarType::Bool, ReadWrite;
+    "roundLockdownCountdown" => VarType::Integer { min: 0, max: 3600 }, ReadWrite;
+    "roundRestartPlayerCount" => VarType::Integer { min: 0, max: 70 }, ReadWrite;
+    "roundStartPlayerCount" => VarType::Integer { min: 0, max: 70 }, ReadWrite;
+    "roundTimeLimit" => PERCENT, ReadWrite;
+    "roundWarmupTimeout" => VarType::Integer { min: 0, max: 3600 }, ReadWrite;
+    "serverDescription" => VarType::String { max_len: 256 }, ReadWrite;
+    "serverMessage" => VarType::String { max_len: 256 }, ReadWrite;
+    "serverName" => VarType::String { max_len: 64 }, ReadWrite;
+    "serverType" => VarType::String { max_len: 64 }, ReadOnly;
+    "soldierHealth" => VarType::Integer { min: 1, max: 300 }, ReadWrite;
+    "teamFactionOverride" => VarType::TeamFactions, Startup;
+    "teamKillCountForKick" => VarType::Integer { min: 0, max: 1000 }, ReadWrite;
+    "teamKillKickForBan" => VarType::Integer { min: 0, max: 1000 }, ReadWrite;
+    "teamKillValueDecreasePerSecond" => VarType::Integer { min: 0, max: 1000 }, ReadWrite;
+    "teamKillValueForKick" => VarType::Integer { min: 0, max: 1000 }, ReadWrite;
+    "teamKillValueIncrease" => VarType::Integer { min: 0, max: 1000 }, ReadWrite;
+    "ticketBleedRate" => PERCENT, ReadWrite;
+    "unlockMode" => VarType::Choice(UNLOCK_MODES), Startup;
+    "vehicleSpawnAllowed" => VarType::Bool, ReadWrite;
+    "vehicleSpawnDelay" => PERCENT, ReadWrite;
+};
+
+/// Looks up a variable by name, without the `vars.` prefix.
+pub fn var_spec(name: &str) -> Option<&'static VarSpec> {
+    VARS.iter().find(|spec| spec.name == name)
+}
+
+impl VarSpec {
+    /// Returns the command getting or setting the variable.
+    pub fn command(&self) -> String {
+        format!("vars.{}", self.name)
+    }
+
+    /// Checks the value can be set.
+    pub fn validate(&self, value: &VarValue) -> Result<(), VarError> {
+        let name = self.name;
+        if self.access == VarAccess::ReadOnly {
+            return Err(VarError::ReadOnly(name));
+        }
+        match (self.ty, value) {
+            (VarType::Bool, VarValue::Bool(_)) => Ok(()),
+            (VarType::Integer { min, max }, VarValue::Integer(value)) => {
+                if *value < min || *value > max {
+                    Err(VarError::OutOfRange {
+                        name,
+                        value: *value,
+                        min,
+                        max,
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+            (VarType::String { max_len }, VarValue::String(s)) => {
+                if s.len() > max_len {
+                    Err(VarError::TooLong {
+                        name,
+                        len: s.len(),
+                        max: max_len,
+                    })
+                } else if Word::new(s).is_err() {
+                    Err(VarError::InvalidValue { name })
+                } else {
+                    Ok(())
+                }
+            }
+            (VarType::Choice(choices), VarValue::String(s)) => {
+                if choices.iter().any(|c| c.eq_ignore_ascii_case(s)) {
+                    Ok(())
+                } else {
+                    Err(VarError::InvalidValue { name })
+                }
+            }
+            (VarType::TeamFactions, VarValue::TeamFactions(factions)) => {
+                if factions.len() > TEAM_FACTIONS_MAX {
+                    Err(VarError::TooLong {
+                        name,
+                        len: factions.len(),
+                        max: TEAM_FACTIONS_MAX,
+                    })
+                } else if factions.iter().any(|&id| catalogue::faction(id).is_none()) {
+                    Err(VarError::InvalidValue { name })
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Err(VarError::TypeMismatch { name }),
+        }
+    }
+
+    /// Reads the value from the words of a get response, following
+    /// the status.
+    pub fn read_value(&self, reader: &mut WordReader<'_>) -> Result<VarValue, BodyError> {
+        Ok(match self.ty {
+            VarType::Bool => VarValue::Bool(reader.parse()?),
+            VarType::Integer { .. } => VarValue::Integer(reader.parse()?),
+            VarType::String { .. } | VarType::Choice(_) => VarValue::String(reader.parse()?),
+            VarType::TeamFactions => {
+                let mut factions = Vec::new();
+                while !reader.is_empty() {
+                    factions.push(reader.parse()?);
+                }
+                VarValue::TeamFactions(factions)
+            }
+        })
+    }
+
+    /// Returns the argument words of each set request needed for the
+    /// value. Team factions are set one team at a time.
+    pub fn set_arguments(&self, value: &VarValue) -> Result<Vec<Vec<Word>>, VarError> {
+        self.validate(value)?;
+        Ok(match value {
+            VarValue::Bool(value) => vec![vec![value.to_word()]],
+            VarValue::Integer(value) => vec![vec![value.to_word()]],
+            VarValue::String(value) => vec![vec![word_from_string(value.clone())]],
+            VarValue::TeamFactions(factions) => factions
+                .iter()
+                .enumerate()
+                .map(|(i, faction)| vec![(i + 1).to_word(), faction.to_word()])
+                .collect(),
+        })
+    }
+}
+
+impl VarValue {
+    /// Returns `true` if both values are the same setting, comparing
+    /// choices case-insensitively.
+    fn same_as(&self, other: &VarValue, ty: VarType) -> bool {
+        match (ty, self, other) {
+            (VarType::Choice(_), VarValue::String(a), VarValue::String(b)) => {
+                a.eq_ignore_ascii_case(b)
+            }
+            _ => self == other,
+        }
+    }
+}
+
+impl fmt::Display for VarValue {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VarValue::Bool(value) => value.fmt(fmt),
+            VarValue::Integer(value) => value.fmt(fmt),
+            VarValue::String(value) => value.fmt(fmt),
+            VarValue::TeamFactions(factions) => {
+                let factions: Vec<String> = factions.iter().map(u32::to_string).collect();
+                factions.join(" ").fmt(fmt)
+            }
+        }
+    }
+}
+
+impl From<bool> for VarValue {
+    fn from(value: bool) -> Self {
+        VarValue::Bool(value)
+    }
+}
+
+impl From<i64> for VarValue {
+    fn from(value: i64) -> Self {
+        VarValue::Integer(value)
+    }
+}
+
+impl From<&str> for VarValue {
+    fn from(value: &str) -> Self {
+        VarValue::String(value.to_string())
+    }
+}
+
+/// A change of a variable from its current value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarChange {
+    pub spec: &'static VarSpec,
+    /// The current value, if known
+    pub from: Option<VarValue>,
+    pub to: VarValue,
+}
+
+/// A snapshot of server variables, by name.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ServerConfig {
+    pub vars: BTreeMap<&'static str, VarValue>,
+}
+
+impl ServerConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a variable after validating it against the registry.
+    pub fn set<V: Into<VarValue>>(&mut self, name: &str, value: V) -> Result<(), VarError> {
+        let spec = var_spec(name).ok_or_else(|| VarError::UnknownVar(name.to_string()))?;
+        let value = value.into();
+        spec.validate(&value)?;
+        self.vars.insert(spec.name, value);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&VarValue> {
+        self.vars.get(name)
+    }
+
+    /// Returns the changes turning this config into the desired one.
+    ///
+    /// Variables absent from the desired config are left as they are.
+    /// Only variables that can be changed at any time are included, see
+    /// [`ServerConfig::startup_diff`] for the others.
+    pub fn diff(&self, desired: &ServerConfig) -> Vec<VarChange> {
+        self.diff_access(desired, VarAccess::ReadWrite)
+    }
+
+    /// Returns the changes of startup variables, which only take effect
+    /// once set from the server's startup script.
+    pub fn startup_diff(&self, desired: &ServerConfig) -> Vec<VarChange> {
+        self.diff_access(desired, VarAccess::Startup)
+    }
+
+    fn diff_access(&self, desired: &ServerConfig, access: VarAccess) -> Vec<VarChange> {
+        desired
+            .vars
+            .iter()
+            .filter_map(|(name, to)| {
+                let spec = var_spec(name).filter(|spec| spec.access == access)?;
+                let from = self.vars.get(name);
+                if from.is_some_and(|from| from.same_as(to, spec.ty)) {
+                    return None;
+                }
+                Some(VarChange {
+                    spec,
+                    from: from.cloned(),
+                    to: to.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_test() {
+        assert!(VARS.windows(2).all(|w| w[0].name < w[1].name));
+        let spec = var_spec("maxPlayers").unwrap();
+        assert_eq!(spec.command(), "vars.maxPlayers");
+        assert_eq!(spec.validate(&VarValue::Integer(64)), Ok(()));
+        assert_eq!(
+            spec.validate(&VarValue::Integer(80)),
+            Err(VarError::OutOfRange {
+                name: "maxPlayers",
+                value: 80,
+                min: 1,
+                max: 70
+            })
+        );
+        assert_eq!(
+            spec.validate(&VarValue::Bool(true)),
+            Err(VarError::TypeMismatch { name: "maxPlayers" })
+        );
+        let spec = var_spec("preset").unwrap();
+        assert_eq!(spec.validate(&"hardcore".into()), Ok(()));
+        assert_eq!(
+            spec.validate(&"Arcade".into()),
+            Err(VarError::InvalidValue { name: "preset" })
+        );
+        assert_eq!(
+            var_spec("serverType").unwrap().validate(&"RANKED".into()),
+            Err(VarError::ReadOnly("serverType"))
+        );
+        assert!(var_spec("unknown").is_none());
+    }
+
+    #[test]
+    fn read_and_set_test() {
+        let words: Vec<Word> = ["1", "2", "0", "0"]
+            .iter()
+            .map(|w| Word::new(w).unwrap())
+            .collect();
+        let spec = var_spec("teamFactionOverride").unwrap();
+        let value = spec.read_value(&mut WordReader::new(&words)).unwrap();
+        assert_eq!(value, VarValue::TeamFactions(vec![1, 2, 0, 0]));
+        let args = spec.set_arguments(&value).unwrap();
+        assert_eq!(args.len(), 4);
+        assert_eq!(args[1][0].as_str(), "2");
+        assert_eq!(args[1][1].as_str(), "2");
+
+        let name = "teamFactionOverride";
+        assert_eq!(
+            spec.validate(&VarValue::TeamFactions(vec![0; 5])),
+            Err(VarError::TooLong {
+                name,
+                len: 5,
+                max: 4
+            })
+        );
+        assert_eq!(
+            spec.validate(&VarValue::TeamFactions(vec![1, 99])),
+            Err(VarError::InvalidValue { name })
+        );
+    }
+
+    #[test]
+    fn diff_test() {
+        let mut current = ServerConfig::new();
+        current.set("serverName", "My Server").unwrap();
+        current.set("friendlyFire", false).unwrap();
+        current.set("preset", "Normal").unwrap();
+        current.vars.insert("serverType", "RANKED".into());
+
+        let mut desired = current.clone();
+        desired.set("friendlyFire", true).unwrap();
+        desired.set("preset", "NORMAL").unwrap();
+        desired.set("maxPlayers", 32i64).unwrap();
+        desired.vars.insert("serverType", "UNRANKED".into());
+
+        let changes = current.diff(&desired);
+        let names: Vec<&str> = changes.iter().map(|c| c.spec.name).collect();
+        assert_eq!(names, ["friendlyFire", "maxPlayers"]);
+        assert_eq!(changes[0].from, Some(VarValue::Bool(false)));
+        assert_eq!(changes[1].from, None);
+        assert_eq!(changes[1].to, VarValue::Integer(32));
+
+        // Startup variables are left to the startup script.
+        current.set("commander", false).unwrap();
+        desired.set("commander", true).unwrap();
+        desired.set("unlockMode", "all").unwrap();
+        assert_eq!(current.diff(&desired).len(), 2);
+        let changes = current.startup_diff(&desired);
+        let names: Vec<&str> = changes.iter().map(|c| c.spec.name).collect();
+        assert_eq!(names, ["commander", "unlockMode"]);
+
+        assert_eq!(
+            desired.set("maxPlayers", 0i64),
+            Err(VarError::OutOfRange {
+                name: "maxPlayers",
+                value: 0,
+                min: 1,
+                max: 70
+            })
+        );
+    }
+}